@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::{Crane, Move, Stacks};
+
+// The loop five ran before stacks were indexed: every crate is popped and
+// pushed on its own. The 9001 arm is the original push/pop through an
+// intermediate Vec; the 9000 arm moves one crate at a time as that crane does.
+fn legacy_apply(crane: Crane, stacks: &mut HashMap<u32, Vec<char>>, instruction: &Move) {
+    match crane {
+        Crane::CrateMover9000 => {
+            for _i in 0..instruction.quantity {
+                let value = stacks.get_mut(&instruction.source).unwrap().pop().unwrap();
+                stacks
+                    .get_mut(&instruction.destination)
+                    .unwrap()
+                    .push(value);
+            }
+        }
+        Crane::CrateMover9001 => {
+            let mut intermediate: Vec<char> = Vec::new();
+            for _i in 0..instruction.quantity {
+                let value = stacks.get_mut(&instruction.source).unwrap().pop().unwrap();
+                intermediate.push(value);
+            }
+
+            let destination = stacks.get_mut(&instruction.destination).unwrap();
+            for _i in 0..instruction.quantity {
+                destination.push(intermediate.pop().unwrap());
+            }
+        }
+    }
+}
+
+fn legacy_run(crane: Crane, stacks: &Stacks, instructions: &[Move]) -> (Stacks, Duration) {
+    let mut stacks = HashMap::from_iter(stacks.iter().map(|(label, c)| (label, c.clone())));
+
+    let start = Instant::now();
+    for instruction in instructions {
+        legacy_apply(crane, &mut stacks, instruction);
+    }
+    (Stacks::from_iter(stacks), start.elapsed())
+}
+
+// Nine stacks with every crate starting on the first; each move carries the
+// whole pile on to the next stack.
+pub fn workload(crates: usize, moves: usize) -> (Stacks, Vec<Move>) {
+    let mut stacks = Stacks::from_iter((1..=9).map(|label| (label, Vec::new())));
+    stacks.crates[0] = Vec::from_iter((0..crates).map(|i| char::from(b'A' + (i % 26) as u8)));
+
+    let instructions = (0..moves as u32)
+        .map(|i| Move {
+            line: i as usize + 1,
+            quantity: crates as u32,
+            source: i % 9 + 1,
+            destination: (i + 1) % 9 + 1,
+        })
+        .collect();
+
+    (stacks, instructions)
+}
+
+pub fn report(crates: usize, moves: usize) -> Result<String, String> {
+    let (stacks, instructions) = workload(crates, moves);
+    let mut out = format!("{} moves of {} crates each\n", moves, crates);
+
+    for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+        let (expected, legacy) = legacy_run(crane, &stacks, &instructions);
+
+        let mut indexed = stacks.clone();
+        let start = Instant::now();
+        crane
+            .run(&mut indexed, &instructions)
+            .map_err(|e| format!("{}: {}", crane.name(), e))?;
+        let block = start.elapsed();
+
+        if indexed != expected {
+            return Err(format!(
+                "{}: block transfer disagrees with the per-crate loop",
+                crane.name()
+            ));
+        }
+        out += &format!(
+            "{}: per-crate loop {:?}, block transfer {:?} ({:.1}x)\n",
+            crane.name(),
+            legacy,
+            block,
+            legacy.as_secs_f64() / block.as_secs_f64().max(1e-9)
+        );
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{legacy_run, report, workload};
+    use crate::{parse_program, parse_stacks, Crane};
+
+    #[test]
+    fn test_block_transfer_matches_loop() {
+        let drawing =
+            ["    [D]    ", "[N] [C]    ", "[Z] [M] [P]", " 1   2   3 "].map(|l| l.to_string());
+        let example = parse_program(
+            1,
+            [
+                "move 1 from 2 to 1",
+                "move 3 from 1 to 3",
+                "move 2 from 2 to 1",
+                "move 1 from 1 to 2",
+                "move 2 from 3 to 3",
+            ],
+        )
+        .unwrap();
+        let generated = workload(100, 20);
+
+        for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+            for (stacks, instructions) in [
+                (parse_stacks(&drawing).unwrap(), &example[..]),
+                (generated.0.clone(), &generated.1[..]),
+            ] {
+                let (expected, _) = legacy_run(crane, &stacks, instructions);
+                let mut indexed = stacks.clone();
+                crane.run(&mut indexed, instructions).unwrap();
+                assert_eq!(indexed, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let report = report(50, 10).unwrap();
+        assert!(report.starts_with("10 moves of 50 crates each\n"));
+        assert_eq!(report.lines().count(), 3);
+    }
+}
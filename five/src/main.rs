@@ -1,22 +1,65 @@
-use std::collections::HashMap;
-use std::io::BufRead;
-use std::{fs::File, io};
+mod bench;
+mod replay;
+
+use std::io::{BufRead, IsTerminal, Write};
+use std::ops::Index;
+use std::time::Duration;
+use std::{env, fmt, fs::File, io, process, thread};
+
+use crate::replay::Replay;
 
 #[derive(Debug, PartialEq)]
 struct Move {
+    line: usize,
     quantity: u32,
     source: u32,
     destination: u32,
 }
 
+// Stacks are stored by position, ordered by label, so a move only has to
+// find two indices before transferring its crates as one block.
+#[derive(Debug, PartialEq, Clone)]
+struct Stacks {
+    labels: Vec<u32>,
+    crates: Vec<Vec<char>>,
+}
+
+impl Stacks {
+    fn position(&self, label: u32) -> Option<usize> {
+        self.labels.binary_search(&label).ok()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (u32, &Vec<char>)> {
+        self.labels.iter().copied().zip(&self.crates)
+    }
+}
+
+impl FromIterator<(u32, Vec<char>)> for Stacks {
+    fn from_iter<I: IntoIterator<Item = (u32, Vec<char>)>>(iter: I) -> Stacks {
+        let mut stacks = Vec::from_iter(iter);
+        stacks.sort_by_key(|(label, _)| *label);
+        let (labels, crates) = stacks.into_iter().unzip();
+        Stacks { labels, crates }
+    }
+}
+
+impl Index<u32> for Stacks {
+    type Output = Vec<char>;
+
+    fn index(&self, label: u32) -> &Vec<char> {
+        &self.crates[self.position(label).expect("no such stack")]
+    }
+}
+
 fn parse_u32(s: &&str) -> Option<u32> {
-    u32::from_str_radix(s, 10).ok()
+    s.parse().ok()
 }
 
-fn parse_move(str: String) -> Option<Move> {
+fn parse_move(line: usize, str: &str) -> Result<Move, CraneError> {
     let split = Vec::from_iter(str.split(' '));
 
-    if split.get(0) == Some(&"move") {
+    let parsed = if split.len() == 6 && split[0] == "move" && split[2] == "from" && split[4] == "to"
+    {
         let quantity = split.get(1).and_then(parse_u32);
         let source = split.get(3).and_then(parse_u32);
         let destination = split.get(5).and_then(parse_u32);
@@ -24,82 +67,670 @@ fn parse_move(str: String) -> Option<Move> {
         quantity
             .zip(source.zip(destination))
             .map(|(quantity, (source, destination))| Move {
+                line,
                 quantity,
                 source,
                 destination,
             })
     } else {
         None
+    };
+
+    parsed.ok_or(CraneError::MalformedMove {
+        line,
+        text: str.to_string(),
+    })
+}
+
+// Parses the moves that follow the drawing, numbering them from first_line
+// and skipping blank lines. On a malformed line the moves before it are
+// returned alongside the error.
+fn parse_program<S: AsRef<str>>(
+    first_line: usize,
+    lines: impl IntoIterator<Item = S>,
+) -> Result<Vec<Move>, (Vec<Move>, CraneError)> {
+    let mut instructions = Vec::new();
+
+    for (i, text) in lines.into_iter().enumerate() {
+        if text.as_ref().is_empty() {
+            continue;
+        }
+        match parse_move(first_line + i, text.as_ref()) {
+            Ok(instruction) => instructions.push(instruction),
+            Err(e) => return Err((instructions, e)),
+        }
+    }
+
+    Ok(instructions)
+}
+
+fn parse_stacks(header: &[String]) -> Result<Stacks, String> {
+    let (footer, rows) = header
+        .split_last()
+        .ok_or("missing stack drawing before the first blank line")?;
+
+    let labels: Vec<u32> = footer
+        .split_whitespace()
+        .map(|label| {
+            label
+                .parse()
+                .map_err(|_| format!("stack label {:?} is not a number", label))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut columns: Vec<Vec<char>> = vec![Vec::new(); labels.len()];
+
+    for (line, row) in rows.iter().enumerate().rev() {
+        let cells = Vec::from_iter(row.chars());
+
+        for (column, cell) in cells.chunks(4).enumerate() {
+            let value = match cell {
+                ['[', value, ']', ..] => *value,
+                [' ', ' ', ' ', ..] | [' ', ' '] | [' '] => continue,
+                _ => {
+                    return Err(format!(
+                        "line {}: malformed crate {:?} in column {}",
+                        line + 1,
+                        String::from_iter(cell),
+                        column + 1
+                    ))
+                }
+            };
+
+            columns
+                .get_mut(column)
+                .ok_or(format!(
+                    "line {}: crate {} in column {} has no numbered stack",
+                    line + 1,
+                    value,
+                    column + 1
+                ))?
+                .push(value);
+        }
+    }
+
+    let stacks = Stacks::from_iter(labels.iter().copied().zip(columns));
+    if let Some(pair) = stacks.labels.windows(2).find(|pair| pair[0] == pair[1]) {
+        return Err(format!("stack {} is labelled twice", pair[0]));
+    }
+    Ok(stacks)
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Crane {
+    CrateMover9000,
+    CrateMover9001,
+}
+
+impl Crane {
+    fn name(&self) -> &'static str {
+        match self {
+            Crane::CrateMover9000 => "CrateMover 9000",
+            Crane::CrateMover9001 => "CrateMover 9001",
+        }
+    }
+
+    fn apply(
+        &self,
+        stacks: &mut Stacks,
+        index: usize,
+        instruction: &Move,
+    ) -> Result<(), CraneError> {
+        let (source, destination) = check(stacks, index, instruction)?;
+
+        // one crate at a time back onto the same stack leaves it as it was
+        if *self == Crane::CrateMover9000 && source == destination {
+            return Ok(());
+        }
+
+        let from = &mut stacks.crates[source];
+        let mut block = from.split_off(from.len() - instruction.quantity as usize);
+
+        if *self == Crane::CrateMover9000 {
+            block.reverse();
+        }
+
+        stacks.crates[destination].append(&mut block);
+        Ok(())
+    }
+
+    fn run(&self, stacks: &mut Stacks, instructions: &[Move]) -> Result<(), CraneError> {
+        self.run_observed(stacks, instructions, |_, _| {})
+    }
+
+    fn run_observed(
+        &self,
+        stacks: &mut Stacks,
+        instructions: &[Move],
+        mut after: impl FnMut(usize, &Stacks),
+    ) -> Result<(), CraneError> {
+        for (i, instruction) in instructions.iter().enumerate() {
+            self.apply(stacks, i + 1, instruction)?;
+            after(i + 1, stacks);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum CraneError {
+    MalformedMove {
+        line: usize,
+        text: String,
+    },
+    MissingStack {
+        instruction: usize,
+        line: usize,
+        stack: u32,
+    },
+    NotEnoughCrates {
+        instruction: usize,
+        line: usize,
+        stack: u32,
+        requested: u32,
+        available: usize,
+    },
+    NoSuchInstruction {
+        instruction: usize,
+        count: usize,
+    },
+}
+
+impl fmt::Display for CraneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CraneError::MalformedMove { line, text } => {
+                write!(f, "line {}: malformed move {:?}", line, text)
+            }
+            CraneError::MissingStack {
+                instruction,
+                line,
+                stack,
+            } => write!(
+                f,
+                "instruction {} (line {}): there is no stack {}",
+                instruction, line, stack
+            ),
+            CraneError::NotEnoughCrates {
+                instruction,
+                line,
+                stack,
+                requested,
+                available,
+            } => write!(
+                f,
+                "instruction {} (line {}): cannot move {} crates from stack {}, it holds {}",
+                instruction, line, requested, stack, available
+            ),
+            CraneError::NoSuchInstruction { instruction, count } => write!(
+                f,
+                "instruction {}: the program only has {} instructions",
+                instruction, count
+            ),
+        }
+    }
+}
+
+// Returns the positions of the source and destination stacks.
+fn check(stacks: &Stacks, index: usize, instruction: &Move) -> Result<(usize, usize), CraneError> {
+    let position = |stack: u32| {
+        stacks.position(stack).ok_or(CraneError::MissingStack {
+            instruction: index,
+            line: instruction.line,
+            stack,
+        })
+    };
+    let source = position(instruction.source)?;
+    let destination = position(instruction.destination)?;
+
+    let available = stacks.crates[source].len();
+    if (instruction.quantity as usize) > available {
+        return Err(CraneError::NotEnoughCrates {
+            instruction: index,
+            line: instruction.line,
+            stack: instruction.source,
+            requested: instruction.quantity,
+            available,
+        });
+    }
+
+    Ok((source, destination))
+}
+
+// Renders the stacks in the same drawing layout parse_stacks reads, with
+// columns ordered by stack label.
+fn render(stacks: &Stacks) -> String {
+    let height = stacks.crates.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut out = String::new();
+    for level in (0..height).rev() {
+        let row: Vec<String> = stacks
+            .crates
+            .iter()
+            .map(|crates| match crates.get(level) {
+                Some(value) => format!("[{}]", value),
+                None => "   ".to_string(),
+            })
+            .collect();
+        out += &row.join(" ");
+        out.push('\n');
+    }
+
+    let footer: Vec<String> = stacks.labels.iter().map(|l| format!("{:^3}", l)).collect();
+    out += &footer.join(" ");
+    out.push('\n');
+    out
+}
+
+fn tops(stacks: &Stacks) -> String {
+    stacks
+        .crates
+        .iter()
+        .flat_map(|crates| crates.last())
+        .collect()
+}
+
+struct Options {
+    best_effort: bool,
+    animate_every: Option<usize>,
+    delay: Duration,
+    replay: Option<Crane>,
+    bench: Option<usize>,
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        best_effort: false,
+        animate_every: None,
+        delay: Duration::from_millis(100),
+        replay: None,
+        bench: None,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--best-effort" => options.best_effort = true,
+            "--animate" => options.animate_every = options.animate_every.or(Some(1)),
+            "--every" => {
+                let every = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .filter(|n| *n > 0)
+                    .ok_or("--every needs a positive number of moves")?;
+                options.animate_every = Some(every);
+            }
+            "--delay" => {
+                let millis = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or("--delay needs a number of milliseconds")?;
+                options.delay = Duration::from_millis(millis);
+            }
+            "--replay" => {
+                let crane = match args.next().as_deref() {
+                    Some("9000") => Crane::CrateMover9000,
+                    Some("9001") => Crane::CrateMover9001,
+                    _ => return Err("--replay needs a crane model, 9000 or 9001".to_string()),
+                };
+                options.replay = Some(crane);
+            }
+            "--bench" => {
+                let crates = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or("--bench needs a number of crates per move")?;
+                options.bench = Some(crates);
+            }
+            _ => return Err(format!("unknown argument {:?}", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+// On a terminal each frame replaces the previous one; otherwise frames are
+// printed one after another so they can be paged through.
+fn show_frame(heading: &str, stacks: &Stacks, delay: Duration) {
+    if io::stdout().is_terminal() {
+        print!("\x1b[2J\x1b[H{}\n{}", heading, render(stacks));
+        thread::sleep(delay);
+    } else {
+        print!("{}\n{}\n", heading, render(stacks));
+    }
+}
+
+fn prompt(mut replay: Replay) {
+    let mut input = io::stdin().lock().lines();
+
+    loop {
+        print!(
+            "{}move {} of {}\n> ",
+            render(replay.stacks()),
+            replay.position(),
+            replay.len()
+        );
+        io::stdout().flush().unwrap();
+
+        let Some(Ok(line)) = input.next() else {
+            println!();
+            return;
+        };
+        let words = Vec::from_iter(line.split_whitespace());
+
+        let result = match words.as_slice() {
+            [] | ["n"] | ["next"] => replay.step().map(|_| ()),
+            ["b"] | ["back"] => {
+                replay.step_back();
+                Ok(())
+            }
+            ["s" | "seek", n] => match n.parse() {
+                Ok(n) => replay.seek(n),
+                Err(_) => {
+                    println!("not an instruction number: {}", n);
+                    continue;
+                }
+            },
+            ["q"] | ["quit"] => return,
+            _ => {
+                println!("commands: next (n), back (b), seek (s) <move>, quit (q)");
+                continue;
+            }
+        };
+
+        if let Err(e) = result {
+            println!("{}", e);
+        }
     }
 }
 
 fn main() {
-    /*
-    [G]         [P]         [M]    
-    [V]     [M] [W] [S]     [Q]    
-    [N]     [N] [G] [H]     [T] [F]
-    [J]     [W] [V] [Q] [W] [F] [P]
-[C] [H]     [T] [T] [G] [B] [Z] [B]
-[S] [W] [S] [L] [F] [B] [P] [C] [H]
-[G] [M] [Q] [S] [Z] [T] [J] [D] [S]
-[B] [T] [M] [B] [J] [C] [T] [G] [N]
- 1   2   3   4   5   6   7   8   9 
-    */
-
-    let mut stacks: HashMap<u32, Vec<char>> = HashMap::new();
-
-
-    stacks.insert(1, vec!['B', 'G', 'S', 'C']);
-    stacks.insert(2, vec!['T', 'M', 'W', 'H', 'J', 'N', 'V', 'G']);
-    stacks.insert(3, vec!['M', 'Q', 'S']);
-    stacks.insert(4, vec!['B', 'S', 'L',  'T', 'W', 'N', 'M']);
-    stacks.insert(5, vec!['J', 'Z', 'F', 'T', 'V', 'G',  'W', 'P']);
-    stacks.insert(6, vec!['C', 'T', 'B', 'G', 'Q', 'H',  'S']);
-    stacks.insert(7, vec!['T', 'J', 'P', 'B', 'W']);
-    stacks.insert(8, vec!['G', 'D', 'C', 'Z', 'F', 'T', 'Q', 'M']);
-    stacks.insert(9, vec!['N', 'S', 'H', 'B', 'P', 'F']);
+    let options = parse_options(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!(
+            "usage: five [--best-effort] [--animate] [--every <moves>] [--delay <ms>] [--replay <9000|9001>] [--bench <crates>]"
+        );
+        process::exit(2);
+    });
+
+    if let Some(crates) = options.bench {
+        match bench::report(crates, 100) {
+            Ok(report) => print!("{}", report),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
 
     let file = File::open("./input").unwrap();
     let lines = io::BufReader::new(file).lines();
 
-    let strings = lines.flat_map(|l| l.ok());
-    let instructions = strings.into_iter().flat_map(parse_move);
+    let mut strings = lines.enumerate().map(|(i, line)| {
+        line.unwrap_or_else(|e| {
+            eprintln!("line {}: cannot read input: {}", i + 1, e);
+            process::exit(1);
+        })
+    });
+    let header = Vec::from_iter(strings.by_ref().take_while(|line| !line.is_empty()));
 
-    for instruction in instructions {
-        let mut intermediate: Vec<char> = Vec::new();
-        for _i in 0..instruction.quantity {
-            let value = stacks.get_mut(&instruction.source).unwrap().pop().unwrap();
-            intermediate.push(value);
-        }
+    let stacks = parse_stacks(&header).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
 
-        let destination = stacks.get_mut(&instruction.destination).unwrap();
-        for _i in 0..instruction.quantity {
-            destination.push(intermediate.pop().unwrap());
+    // the moves start after the drawing and the blank line that ends it
+    let (instructions, malformed) = match parse_program(header.len() + 2, strings) {
+        Ok(instructions) => (instructions, None),
+        Err((instructions, e)) if options.best_effort => (instructions, Some(e)),
+        Err((_, e)) => {
+            eprintln!("{}", e);
+            process::exit(1);
         }
+    };
+
+    if let Some(crane) = options.replay {
+        prompt(Replay::new(crane, stacks, &instructions, 64));
+        return;
     }
-    
-    for i in 1..10 {
-        let value = stacks.get_mut(&i).unwrap().pop().unwrap();
-        print!("{}", value);
+
+    for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+        let mut stacks = stacks.clone();
+
+        let result = match options.animate_every {
+            Some(every) => {
+                show_frame(&format!("{}: start", crane.name()), &stacks, options.delay);
+                crane.run_observed(&mut stacks, &instructions, |n, stacks| {
+                    if n % every == 0 || n == instructions.len() {
+                        let heading =
+                            format!("{}: move {} of {}", crane.name(), n, instructions.len());
+                        show_frame(&heading, stacks, options.delay);
+                    }
+                })
+            }
+            None => crane.run(&mut stacks, &instructions),
+        };
+        let result = result.and_then(|()| malformed.clone().map_or(Ok(()), Err));
+
+        match result {
+            Ok(()) => println!("{}: {}", crane.name(), tops(&stacks)),
+            Err(e) if options.best_effort => {
+                println!("{}: stopped, {}", crane.name(), e);
+                print!("{}", render(&stacks));
+                println!("{}: {}", crane.name(), tops(&stacks));
+            }
+            Err(e) => {
+                eprintln!("{}: {}", crane.name(), e);
+                process::exit(1);
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{parse_move, Move};
+    use crate::{
+        parse_move, parse_program, parse_stacks, render, tops, Crane, CraneError, Move, Stacks,
+    };
 
     #[test]
     fn test_parse_move() {
-        let input = "move 12 from 5 to 8".to_string();
         assert_eq!(
-            parse_move(input),
-            Some(Move {
+            parse_move(3, "move 12 from 5 to 8"),
+            Ok(Move {
+                line: 3,
                 quantity: 12,
                 source: 5,
                 destination: 8
             })
         );
-        let input2 = "    [G]         [P]         [M]    ".to_string();
-        assert_eq!(parse_move(input2), None);
+        for malformed in [
+            "    [G]         [P]         [M]    ",
+            "move x from 1 to 2",
+            "move 1 from 2",
+            "move 1 to 2 from 3",
+        ] {
+            assert_eq!(
+                parse_move(4, malformed),
+                Err(CraneError::MalformedMove {
+                    line: 4,
+                    text: malformed.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_program() {
+        let lines = [
+            "move 1 from 2 to 1",
+            "",
+            "move x from 1 to 2",
+            "move 1 from 1 to 2",
+        ];
+        let (instructions, e) = parse_program(6, lines).unwrap_err();
+
+        assert_eq!(Vec::from_iter(instructions.iter().map(|m| m.line)), vec![6]);
+        assert_eq!(
+            e.to_string(),
+            "line 8: malformed move \"move x from 1 to 2\""
+        );
+        assert_eq!(parse_program(6, &lines[..2]).unwrap().len(), 1);
+    }
+
+    fn header(lines: &[&str]) -> Vec<String> {
+        Vec::from_iter(lines.iter().map(|l| l.to_string()))
+    }
+
+    #[test]
+    fn test_parse_stacks() {
+        let drawing = header(&["    [D]    ", "[N] [C]    ", "[Z] [M] [P]", " 1   2   3 "]);
+        assert_eq!(
+            parse_stacks(&drawing),
+            Ok(Stacks::from_iter([
+                (1, vec!['Z', 'N']),
+                (2, vec!['M', 'C', 'D']),
+                (3, vec!['P'])
+            ]))
+        );
+
+        let trimmed = header(&["    [D]", "[N] [C]", "[Z] [M] [P]", " 1   2   3"]);
+        assert_eq!(parse_stacks(&trimmed), parse_stacks(&drawing));
+    }
+
+    #[test]
+    fn test_parse_stacks_many_columns() {
+        let drawing = header(&[
+            "[A]                                     [K]",
+            " 1   2   3   4   5   6   7   8   9  10  11 ",
+        ]);
+        let stacks = parse_stacks(&drawing).unwrap();
+        assert_eq!(stacks.labels.len(), 11);
+        assert_eq!(stacks[1], vec!['A']);
+        assert_eq!(stacks[11], vec!['K']);
+        assert_eq!(stacks[10], vec![]);
+    }
+
+    #[test]
+    fn test_parse_stacks_errors() {
+        assert!(parse_stacks(&[]).is_err());
+        assert!(parse_stacks(&header(&["[A]", " x "])).is_err());
+        assert!(parse_stacks(&header(&["[A] [B]", " 1 "])).is_err());
+        assert!(parse_stacks(&header(&["[A] B  ", " 1   2 "])).is_err());
+        assert!(parse_stacks(&header(&["[A] [B]", " 1   1 "])).is_err());
+    }
+
+    #[test]
+    fn test_cranes() {
+        let drawing = header(&["    [D]    ", "[N] [C]    ", "[Z] [M] [P]", " 1   2   3 "]);
+        let instructions = parse_program(
+            1,
+            [
+                "move 1 from 2 to 1",
+                "move 3 from 1 to 3",
+                "move 2 from 2 to 1",
+                "move 1 from 1 to 2",
+            ],
+        )
+        .unwrap();
+
+        let run = |crane: Crane| {
+            let mut stacks = parse_stacks(&drawing).unwrap();
+            crane.run(&mut stacks, &instructions).unwrap();
+            tops(&stacks)
+        };
+
+        assert_eq!(run(Crane::CrateMover9000), "CMZ");
+        assert_eq!(run(Crane::CrateMover9001), "MCD");
+    }
+
+    #[test]
+    fn test_move_onto_same_stack() {
+        let drawing = header(&["[C]", "[B]", "[A]", " 1 "]);
+        let instructions = parse_program(1, ["move 2 from 1 to 1"]).unwrap();
+
+        for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+            let mut stacks = parse_stacks(&drawing).unwrap();
+            crane.run(&mut stacks, &instructions).unwrap();
+            assert_eq!(stacks, parse_stacks(&drawing).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_invalid_moves() {
+        let drawing = header(&["    [D]    ", "[N] [C]    ", "[Z] [M] [P]", " 1   2   3 "]);
+        let instructions = parse_program(
+            1,
+            [
+                "move 1 from 2 to 1",
+                "move 2 from 3 to 1",
+                "move 1 from 4 to 1",
+            ],
+        )
+        .unwrap();
+
+        let mut stacks = parse_stacks(&drawing).unwrap();
+        assert_eq!(
+            Crane::CrateMover9000.run(&mut stacks, &instructions),
+            Err(CraneError::NotEnoughCrates {
+                instruction: 2,
+                line: 2,
+                stack: 3,
+                requested: 2,
+                available: 1
+            })
+        );
+        assert_eq!(tops(&stacks), "DCP");
+
+        assert_eq!(
+            Crane::CrateMover9001.run(&mut stacks, &instructions[2..]),
+            Err(CraneError::MissingStack {
+                instruction: 1,
+                line: 3,
+                stack: 4
+            })
+        );
+        assert_eq!(
+            CraneError::MissingStack {
+                instruction: 1,
+                line: 3,
+                stack: 4
+            }
+            .to_string(),
+            "instruction 1 (line 3): there is no stack 4"
+        );
+    }
+
+    #[test]
+    fn test_render_round_trips() {
+        let drawing = header(&["    [D]    ", "[N] [C]    ", "[Z] [M] [P]", " 1   2   3 "]);
+        let stacks = parse_stacks(&drawing).unwrap();
+        let rendered = render(&stacks);
+
+        assert_eq!(rendered, drawing.join("\n") + "\n");
+
+        let mut moved = stacks.clone();
+        let instructions = parse_program(1, ["move 3 from 2 to 1"]).unwrap();
+        let mut frames = Vec::new();
+        Crane::CrateMover9001
+            .run_observed(&mut moved, &instructions, |n, stacks| {
+                frames.push((n, render(stacks)))
+            })
+            .unwrap();
+
+        assert_eq!(frames.len(), 1);
+        let lines = Vec::from_iter(frames[0].1.lines().map(|l| l.to_string()));
+        assert_eq!(lines[0], "[D]        ");
+        assert_eq!(parse_stacks(&lines), Ok(moved));
+    }
+
+    #[test]
+    fn test_render_wide_labels() {
+        let stacks = Stacks::from_iter((1..=11).map(|l| (l, vec![char::from(b'@' + l as u8)])));
+        let rendered = render(&stacks);
+        let lines = Vec::from_iter(rendered.lines().map(|l| l.to_string()));
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(parse_stacks(&lines), Ok(stacks));
     }
 }
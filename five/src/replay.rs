@@ -0,0 +1,221 @@
+use crate::{Crane, CraneError, Move, Stacks};
+
+// The applied prefix of the program is the undo log: the inverse of move n is
+// instruction n with source and destination swapped, since taking the same
+// number of crates back with the same crane restores their original order,
+// whether or not the crane reverses them.
+pub struct Replay<'a> {
+    crane: Crane,
+    instructions: &'a [Move],
+    stacks: Stacks,
+    position: usize,
+    snapshots: Vec<Stacks>,
+    interval: usize,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(
+        crane: Crane,
+        stacks: Stacks,
+        instructions: &'a [Move],
+        interval: usize,
+    ) -> Replay<'a> {
+        Replay {
+            crane,
+            instructions,
+            snapshots: vec![stacks.clone()],
+            stacks,
+            position: 0,
+            interval: interval.max(1),
+        }
+    }
+
+    pub fn stacks(&self) -> &Stacks {
+        &self.stacks
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn step(&mut self) -> Result<bool, CraneError> {
+        let Some(instruction) = self.instructions.get(self.position) else {
+            return Ok(false);
+        };
+
+        self.crane
+            .apply(&mut self.stacks, self.position + 1, instruction)?;
+        self.position += 1;
+
+        if self.position == self.snapshots.len() * self.interval {
+            self.snapshots.push(self.stacks.clone());
+        }
+        Ok(true)
+    }
+
+    pub fn step_back(&mut self) -> bool {
+        let Some(applied) = self.position.checked_sub(1) else {
+            return false;
+        };
+        let instruction = &self.instructions[applied];
+        let inverse = Move {
+            line: instruction.line,
+            quantity: instruction.quantity,
+            source: instruction.destination,
+            destination: instruction.source,
+        };
+
+        self.crane
+            .apply(&mut self.stacks, self.position, &inverse)
+            .expect("undoing an applied move cannot fail");
+        self.position -= 1;
+        true
+    }
+
+    pub fn seek(&mut self, target: usize) -> Result<(), CraneError> {
+        if target > self.instructions.len() {
+            return Err(CraneError::NoSuchInstruction {
+                instruction: target,
+                count: self.instructions.len(),
+            });
+        }
+
+        // restore the nearest snapshot when it is closer than undoing or
+        // replaying from where we are
+        let snapshot = (target / self.interval).min(self.snapshots.len() - 1);
+        let from_snapshot = target - snapshot * self.interval;
+        if from_snapshot < self.position.abs_diff(target) {
+            self.stacks = self.snapshots[snapshot].clone();
+            self.position = snapshot * self.interval;
+        }
+
+        while self.position > target {
+            self.step_back();
+        }
+        while self.position < target {
+            self.step()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Replay;
+    use crate::{parse_program, parse_stacks, tops, Crane, CraneError, Move};
+
+    fn example() -> (Vec<String>, Vec<Move>) {
+        let drawing = ["    [D]    ", "[N] [C]    ", "[Z] [M] [P]", " 1   2   3 "]
+            .map(|l| l.to_string())
+            .to_vec();
+        let instructions = parse_program(
+            1,
+            [
+                "move 1 from 2 to 1",
+                "move 3 from 1 to 3",
+                "move 2 from 2 to 1",
+                "move 1 from 1 to 2",
+            ],
+        )
+        .unwrap();
+        (drawing, instructions)
+    }
+
+    #[test]
+    fn test_step_back_restores_every_state() {
+        let (drawing, instructions) = example();
+
+        for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+            let stacks = parse_stacks(&drawing).unwrap();
+            let mut replay = Replay::new(crane, stacks.clone(), &instructions, 2);
+
+            let mut states = vec![stacks];
+            while replay.step().unwrap() {
+                states.push(replay.stacks().clone());
+            }
+            assert_eq!(replay.position(), replay.len());
+
+            while replay.step_back() {
+                assert_eq!(replay.stacks(), &states[replay.position()]);
+            }
+            assert_eq!(replay.position(), 0);
+        }
+    }
+
+    #[test]
+    fn test_seek() {
+        let (drawing, instructions) = example();
+        let stacks = parse_stacks(&drawing).unwrap();
+        let mut replay = Replay::new(Crane::CrateMover9001, stacks, &instructions, 2);
+
+        replay.seek(4).unwrap();
+        assert_eq!(tops(replay.stacks()), "MCD");
+
+        for target in [1, 3, 0, 2, 4, 1] {
+            replay.seek(target).unwrap();
+            assert_eq!(replay.position(), target);
+
+            let mut expected = parse_stacks(&drawing).unwrap();
+            Crane::CrateMover9001
+                .run(&mut expected, &instructions[..target])
+                .unwrap();
+            assert_eq!(replay.stacks(), &expected);
+        }
+
+        assert_eq!(
+            replay.seek(5),
+            Err(CraneError::NoSuchInstruction {
+                instruction: 5,
+                count: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_seek_across_snapshots() {
+        let drawing = [
+            "[A] [E] [I]",
+            "[B] [F] [J]",
+            "[C] [G] [K]",
+            "[D] [H] [L]",
+            " 1   2   3 ",
+        ]
+        .map(|l| l.to_string());
+        let instructions = parse_program(
+            1,
+            [
+                "move 2 from 1 to 2",
+                "move 1 from 3 to 1",
+                "move 3 from 2 to 3",
+                "move 2 from 1 to 2",
+                "move 4 from 3 to 1",
+                "move 1 from 2 to 3",
+                "move 3 from 1 to 2",
+                "move 2 from 2 to 1",
+                "move 1 from 3 to 2",
+                "move 2 from 1 to 3",
+                "move 3 from 2 to 1",
+                "move 1 from 1 to 3",
+            ],
+        )
+        .unwrap();
+
+        for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+            let stacks = parse_stacks(&drawing).unwrap();
+            let mut replay = Replay::new(crane, stacks.clone(), &instructions, 4);
+
+            for target in [12, 2, 9, 7, 11, 0, 5, 3] {
+                replay.seek(target).unwrap();
+                assert_eq!(replay.position(), target);
+
+                let mut expected = stacks.clone();
+                crane.run(&mut expected, &instructions[..target]).unwrap();
+                assert_eq!(replay.stacks(), &expected);
+            }
+        }
+    }
+}
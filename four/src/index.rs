@@ -0,0 +1,208 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+
+use crate::{Assignment, AssignmentPair};
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Hash)]
+pub struct Elf {
+    pub line: usize,
+    pub position: usize,
+}
+
+impl fmt::Display for Elf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.line, self.position)
+    }
+}
+
+// Entries are sorted by lower bound and read as an implicit balanced tree:
+// the node for a range [lo, hi) is its midpoint, and max_upper holds the
+// largest upper bound in that node's subtree.
+pub struct AssignmentIndex {
+    entries: Vec<(Assignment, Elf)>,
+    max_upper: Vec<u32>,
+}
+
+impl AssignmentIndex {
+    pub fn new<'a>(
+        pairs: impl IntoIterator<Item = (usize, &'a AssignmentPair)>,
+    ) -> AssignmentIndex {
+        let mut entries: Vec<(Assignment, Elf)> = pairs
+            .into_iter()
+            .flat_map(|(line, pair)| {
+                [
+                    (pair.a.clone(), Elf { line, position: 1 }),
+                    (pair.b.clone(), Elf { line, position: 2 }),
+                ]
+            })
+            .collect();
+        entries.sort_by_key(|(a, elf)| (a.lower, a.upper, *elf));
+
+        let mut max_upper = vec![0; entries.len()];
+        fill_max_upper(&entries, &mut max_upper, 0, entries.len());
+
+        AssignmentIndex { entries, max_upper }
+    }
+
+    pub fn overlapping(&self, range: &Assignment) -> Vec<Elf> {
+        let mut found = Vec::new();
+        self.search(range, 0, self.entries.len(), &mut found);
+        found.sort();
+        found
+    }
+
+    pub fn touching(&self, section: u32) -> Vec<Elf> {
+        self.overlapping(&Assignment {
+            lower: section,
+            upper: section,
+        })
+    }
+
+    fn search(&self, range: &Assignment, lo: usize, hi: usize, found: &mut Vec<Elf>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        if self.max_upper[mid] < range.lower {
+            return;
+        }
+
+        self.search(range, lo, mid, found);
+
+        let (assignment, elf) = &self.entries[mid];
+        if assignment.lower > range.upper {
+            return;
+        }
+        if assignment.overlaps(range) {
+            found.push(*elf);
+        }
+
+        self.search(range, mid + 1, hi, found);
+    }
+
+    pub fn overlapping_pairs(&self) -> Vec<(Elf, Elf)> {
+        let mut pairs = Vec::new();
+        let mut active: BinaryHeap<Reverse<(u32, Elf)>> = BinaryHeap::new();
+
+        for (assignment, elf) in &self.entries {
+            while let Some(Reverse((upper, _))) = active.peek() {
+                if *upper >= assignment.lower {
+                    break;
+                }
+                active.pop();
+            }
+
+            for Reverse((_, other)) in active.iter() {
+                if other.line != elf.line {
+                    pairs.push((*other.min(elf), *other.max(elf)));
+                }
+            }
+
+            active.push(Reverse((assignment.upper, *elf)));
+        }
+
+        pairs.sort();
+        pairs
+    }
+}
+
+fn fill_max_upper(
+    entries: &[(Assignment, Elf)],
+    max_upper: &mut [u32],
+    lo: usize,
+    hi: usize,
+) -> u32 {
+    if lo >= hi {
+        return 0;
+    }
+    let mid = lo + (hi - lo) / 2;
+    let left = fill_max_upper(entries, max_upper, lo, mid);
+    let right = fill_max_upper(entries, max_upper, mid + 1, hi);
+
+    max_upper[mid] = entries[mid].0.upper.max(left).max(right);
+    max_upper[mid]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AssignmentIndex, Elf};
+    use crate::{parse_assignment_pair, Assignment, AssignmentPair};
+
+    fn example() -> Vec<(usize, AssignmentPair)> {
+        [
+            "2-4,6-8", "2-3,4-5", "5-7,7-9", "2-8,3-7", "6-6,4-6", "2-6,4-8",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, l)| (i + 1, parse_assignment_pair(i + 1, l).unwrap()))
+        .collect()
+    }
+
+    fn elf(line: usize, position: usize) -> Elf {
+        Elf { line, position }
+    }
+
+    fn brute_force_pairs(pairs: &[(usize, AssignmentPair)]) -> Vec<(Elf, Elf)> {
+        let elves: Vec<(Assignment, Elf)> = pairs
+            .iter()
+            .flat_map(|(line, p)| [(p.a.clone(), elf(*line, 1)), (p.b.clone(), elf(*line, 2))])
+            .collect();
+        let mut found = Vec::new();
+        for (i, (a, x)) in elves.iter().enumerate() {
+            for (b, y) in &elves[i + 1..] {
+                if x.line != y.line && a.overlaps(b) {
+                    found.push((*x.min(y), *x.max(y)));
+                }
+            }
+        }
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn test_touching() {
+        let pairs = example();
+        let index = AssignmentIndex::new(pairs.iter().map(|(line, p)| (*line, p)));
+
+        assert_eq!(
+            index.touching(3),
+            vec![elf(1, 1), elf(2, 1), elf(4, 1), elf(4, 2), elf(6, 1)]
+        );
+        assert_eq!(index.touching(9), vec![elf(3, 2)]);
+        assert_eq!(index.touching(10), vec![]);
+    }
+
+    #[test]
+    fn test_overlapping_pairs_matches_brute_force() {
+        let pairs = example();
+        let index = AssignmentIndex::new(pairs.iter().map(|(line, p)| (*line, p)));
+        let found = index.overlapping_pairs();
+
+        assert_eq!(found, brute_force_pairs(&pairs));
+        assert!(found.iter().all(|(x, y)| x.line != y.line));
+        assert!(found.contains(&(elf(1, 1), elf(2, 1))));
+    }
+
+    #[test]
+    fn test_overlapping_matches_linear_scan() {
+        let pairs = example();
+        let index = AssignmentIndex::new(pairs.iter().map(|(line, p)| (*line, p)));
+
+        for lower in 0..11 {
+            for upper in lower..11 {
+                let query = Assignment { lower, upper };
+                let mut expected: Vec<Elf> = pairs
+                    .iter()
+                    .flat_map(|(line, p)| {
+                        [(p.a.clone(), elf(*line, 1)), (p.b.clone(), elf(*line, 2))]
+                    })
+                    .filter(|(a, _)| a.overlaps(&query))
+                    .map(|(_, e)| e)
+                    .collect();
+                expected.sort();
+                assert_eq!(index.overlapping(&query), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::Assignment;
+
+#[derive(PartialEq, Debug)]
+pub struct IntervalSet {
+    union: Vec<Assignment>,
+    max_depth: usize,
+    busiest_section: Option<u32>,
+}
+
+impl IntervalSet {
+    pub fn from_assignments<'a>(
+        assignments: impl IntoIterator<Item = &'a Assignment>,
+    ) -> IntervalSet {
+        // each assignment covers [lower, upper + 1), so ends are kept as u64
+        let mut events: Vec<(u64, i64)> = Vec::new();
+        for assignment in assignments {
+            events.push((assignment.lower as u64, 1));
+            events.push((assignment.upper as u64 + 1, -1));
+        }
+        events.sort_unstable();
+
+        let mut union = Vec::new();
+        let mut max_depth = 0;
+        let mut busiest_section = None;
+        let mut depth: i64 = 0;
+        let mut open = 0;
+
+        let mut i = 0;
+        while i < events.len() {
+            let position = events[i].0;
+            let before = depth;
+            while i < events.len() && events[i].0 == position {
+                depth += events[i].1;
+                i += 1;
+            }
+
+            if before == 0 && depth > 0 {
+                open = position;
+            } else if before > 0 && depth == 0 {
+                union.push(Assignment {
+                    lower: open as u32,
+                    upper: (position - 1) as u32,
+                });
+            }
+
+            if depth as usize > max_depth {
+                max_depth = depth as usize;
+                busiest_section = Some(position as u32);
+            }
+        }
+
+        IntervalSet {
+            union,
+            max_depth,
+            busiest_section,
+        }
+    }
+
+    pub fn union(&self) -> &[Assignment] {
+        &self.union
+    }
+
+    pub fn covered(&self) -> u64 {
+        self.union
+            .iter()
+            .map(|a| (a.upper - a.lower) as u64 + 1)
+            .sum()
+    }
+
+    pub fn gaps(&self) -> Vec<Assignment> {
+        self.union
+            .windows(2)
+            .map(|pair| Assignment {
+                lower: pair[0].upper + 1,
+                upper: pair[1].lower - 1,
+            })
+            .collect()
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn busiest_section(&self) -> Option<u32> {
+        self.busiest_section
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntervalSet;
+    use crate::Assignment;
+
+    fn range(lower: u32, upper: u32) -> Assignment {
+        Assignment { lower, upper }
+    }
+
+    #[test]
+    fn test_union_merges_overlapping_and_adjacent() {
+        let assignments = [
+            range(5, 7),
+            range(2, 4),
+            range(7, 9),
+            range(12, 15),
+            range(14, 14),
+        ];
+        let set = IntervalSet::from_assignments(&assignments);
+
+        assert_eq!(set.union(), &[range(2, 9), range(12, 15)]);
+        assert_eq!(set.covered(), 8 + 4);
+        assert_eq!(set.gaps(), vec![range(10, 11)]);
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let assignments = [range(1, 10), range(3, 6), range(5, 8), range(6, 6)];
+        let set = IntervalSet::from_assignments(&assignments);
+
+        assert_eq!(set.max_depth(), 4);
+        assert_eq!(set.busiest_section(), Some(6));
+    }
+
+    #[test]
+    fn test_edges() {
+        let empty = IntervalSet::from_assignments(&[]);
+        assert_eq!(empty.union(), &[]);
+        assert_eq!(empty.covered(), 0);
+        assert_eq!(empty.max_depth(), 0);
+        assert_eq!(empty.busiest_section(), None);
+
+        let full = IntervalSet::from_assignments(&[range(0, u32::MAX)]);
+        assert_eq!(full.union(), &[range(0, u32::MAX)]);
+        assert_eq!(full.covered(), u32::MAX as u64 + 1);
+        assert_eq!(full.gaps(), vec![]);
+    }
+}
@@ -1,18 +1,38 @@
+mod index;
+mod intervals;
+
 use std::io::BufRead;
-use std::{fs::File, io};
+use std::{env, fmt, fs::File, io, process};
 
-#[derive(PartialEq, Debug)]
+use index::AssignmentIndex;
+use intervals::IntervalSet;
+
+#[derive(PartialEq, Debug, Clone)]
 struct Assignment {
     lower: u32,
     upper: u32,
 }
 
 impl Assignment {
-    fn consumes(&self, other: &Assignment) -> bool {
-        self.lower <= other.lower && other.lower <= self.upper
-            || self.lower <= other.upper && other.upper <= self.upper
-            || other.lower <= self.lower && self.lower <= other.upper
-            || other.lower <= self.upper && self.upper <= other.upper
+    fn fully_contains(&self, other: &Assignment) -> bool {
+        self.lower <= other.lower && other.upper <= self.upper
+    }
+
+    fn overlaps(&self, other: &Assignment) -> bool {
+        self.lower <= other.upper && other.lower <= self.upper
+    }
+
+    fn is_adjacent(&self, other: &Assignment) -> bool {
+        self.upper.checked_add(1) == Some(other.lower)
+            || other.upper.checked_add(1) == Some(self.lower)
+    }
+
+    fn overlap_len(&self, other: &Assignment) -> u32 {
+        if self.overlaps(other) {
+            self.upper.min(other.upper) - self.lower.max(other.lower) + 1
+        } else {
+            0
+        }
     }
 }
 
@@ -23,60 +43,332 @@ struct AssignmentPair {
 }
 
 impl AssignmentPair {
+    fn has_containment(&self) -> bool {
+        self.a.fully_contains(&self.b) || self.b.fully_contains(&self.a)
+    }
+
     fn has_overlap(&self) -> bool {
-        self.a.consumes(&self.b) || self.b.consumes(&self.a)
+        self.a.overlaps(&self.b)
+    }
+
+    fn is_adjacent(&self) -> bool {
+        self.a.is_adjacent(&self.b)
+    }
+
+    fn overlap_len(&self) -> u32 {
+        self.a.overlap_len(&self.b)
+    }
+}
+
+#[derive(PartialEq, Debug, Default)]
+struct Counts {
+    pairs: usize,
+    containing: usize,
+    overlapping: usize,
+    adjacent: usize,
+    overlapping_sections: u64,
+}
+
+impl Counts {
+    fn add(mut self, pair: &AssignmentPair) -> Counts {
+        self.pairs += 1;
+        self.containing += pair.has_containment() as usize;
+        self.overlapping += pair.has_overlap() as usize;
+        self.adjacent += pair.is_adjacent() as usize;
+        self.overlapping_sections += pair.overlap_len() as u64;
+        self
+    }
+}
+
+#[derive(PartialEq, Debug)]
+enum ParseErrorKind {
+    MissingComma,
+    MissingDash { assignment: String },
+    NonNumericBound { bound: String },
+    InvertedRange { lower: u32, upper: u32 },
+    InvalidUtf8,
+    ReadFailed { message: String },
+}
+
+#[derive(PartialEq, Debug)]
+struct ParseError {
+    line: usize,
+    kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ParseErrorKind::MissingComma => write!(f, "expected two assignments separated by ','"),
+            ParseErrorKind::MissingDash { assignment } => {
+                write!(f, "expected a range like 2-4, found {:?}", assignment)
+            }
+            ParseErrorKind::NonNumericBound { bound } => {
+                write!(f, "section bound {:?} is not a number", bound)
+            }
+            ParseErrorKind::InvertedRange { lower, upper } => {
+                write!(f, "range {}-{} ends before it starts", lower, upper)
+            }
+            ParseErrorKind::InvalidUtf8 => write!(f, "line is not valid UTF-8"),
+            ParseErrorKind::ReadFailed { message } => write!(f, "cannot read input: {}", message),
+        }
+    }
+}
+
+fn parse_u32(s: &str) -> Result<u32, ParseErrorKind> {
+    s.parse().map_err(|_| ParseErrorKind::NonNumericBound {
+        bound: s.to_string(),
+    })
+}
+
+fn parse_assignment(s: &str) -> Result<Assignment, ParseErrorKind> {
+    let (lower, upper) = s.split_once('-').ok_or(ParseErrorKind::MissingDash {
+        assignment: s.to_string(),
+    })?;
+    let lower = parse_u32(lower)?;
+    let upper = parse_u32(upper)?;
+
+    if lower > upper {
+        return Err(ParseErrorKind::InvertedRange { lower, upper });
+    }
+
+    Ok(Assignment { lower, upper })
+}
+
+fn parse_assignment_pair(line_number: usize, line: &str) -> Result<AssignmentPair, ParseError> {
+    let error = |kind| ParseError {
+        line: line_number,
+        kind,
+    };
+
+    let (a, b) = line
+        .split_once(',')
+        .ok_or(error(ParseErrorKind::MissingComma))?;
+    let a = parse_assignment(a).map_err(error)?;
+    let b = parse_assignment(b).map_err(error)?;
+
+    Ok(AssignmentPair { a, b })
+}
+
+// Invalid UTF-8 spoils only its own line; any other read error ends the
+// input, so it cannot be skipped.
+fn read_assignment_pair(
+    line_number: usize,
+    line: io::Result<String>,
+) -> Result<AssignmentPair, ParseError> {
+    let error = |kind| ParseError {
+        line: line_number,
+        kind,
+    };
+
+    match line {
+        Ok(line) => parse_assignment_pair(line_number, &line),
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => Err(error(ParseErrorKind::InvalidUtf8)),
+        Err(e) => Err(error(ParseErrorKind::ReadFailed {
+            message: e.to_string(),
+        })),
     }
 }
 
-fn parse_u32(s: &&str) -> Option<u32> {
-    u32::from_str_radix(s, 10).ok()
+#[derive(PartialEq, Debug)]
+enum Command {
+    Report,
+    Touching(u32),
+    OverlappingPairs,
+}
+
+#[derive(PartialEq, Debug)]
+struct Options {
+    command: Command,
+    skip_invalid: bool,
 }
 
-fn parse_assignment(s: &&str) -> Option<Assignment> {
-    let bounds = Vec::from_iter(s.split('-'));
-    let lower = bounds.get(0).and_then(parse_u32);
-    let upper = bounds.get(1).and_then(parse_u32);
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let skip_invalid = args.iter().any(|arg| arg == "--skip-invalid");
+    let args = Vec::from_iter(args.iter().filter(|arg| *arg != "--skip-invalid").cloned());
 
-    lower
-        .zip(upper)
-        .map(|(lower, upper)| Assignment { lower, upper })
+    parse_command(&args).map(|command| Options {
+        command,
+        skip_invalid,
+    })
 }
 
-fn parse_assignment_pair(line: String) -> Option<AssignmentPair> {
-    let pair = Vec::from_iter(line.split(','));
-    let a = pair.get(0).and_then(parse_assignment);
-    let b = pair.get(1).and_then(parse_assignment);
-    a.zip(b).map(|(a, b)| AssignmentPair { a, b })
+fn parse_command(args: &[String]) -> Result<Command, String> {
+    match args {
+        [] => Ok(Command::Report),
+        [command, section] if command == "touching" => section
+            .parse()
+            .map(Command::Touching)
+            .map_err(|_| format!("invalid section for touching: {}", section)),
+        [command] if command == "touching" => Err("touching needs a section".to_string()),
+        [command] if command == "overlapping-pairs" => Ok(Command::OverlappingPairs),
+        [command, extra, ..] if command == "touching" || command == "overlapping-pairs" => {
+            Err(format!("unexpected argument: {}", extra))
+        }
+        [arg, ..] => Err(format!("unknown argument: {}", arg)),
+    }
 }
 
 fn main() {
+    let args = Vec::from_iter(env::args().skip(1));
+    let options = parse_options(&args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!("usage: four [touching <section> | overlapping-pairs] [--skip-invalid]");
+        process::exit(2);
+    });
+
     let file = File::open("./input").unwrap();
     let lines = io::BufReader::new(file).lines();
 
-    let strings = lines.flat_map(|l| l.ok());
+    let mut pairs: Vec<(usize, AssignmentPair)> = Vec::new();
+    let mut skipped = 0;
+
+    for (i, line) in lines.enumerate() {
+        match read_assignment_pair(i + 1, line) {
+            Ok(pair) => pairs.push((i + 1, pair)),
+            Err(e)
+                if options.skip_invalid && !matches!(e.kind, ParseErrorKind::ReadFailed { .. }) =>
+            {
+                eprintln!("skipping {}", e);
+                skipped += 1;
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+    }
+    if skipped > 0 {
+        eprintln!("skipped {} invalid lines", skipped);
+    }
+
+    match options.command {
+        Command::Report => report(pairs.iter().map(|(_, pair)| pair)),
+        Command::Touching(section) => {
+            let index = AssignmentIndex::new(pairs.iter().map(|(line, pair)| (*line, pair)));
+            let elves = index.touching(section);
+
+            println!("{} elves touch section {}", elves.len(), section);
+            for elf in elves {
+                println!("  {}", elf);
+            }
+        }
+        Command::OverlappingPairs => {
+            let index = AssignmentIndex::new(pairs.iter().map(|(line, pair)| (*line, pair)));
+            let overlapping = index.overlapping_pairs();
+
+            println!("{} overlapping pairs across lines", overlapping.len());
+            for (a, b) in overlapping {
+                println!("  {} {}", a, b);
+            }
+        }
+    }
+}
+
+fn report<'a>(pairs: impl Iterator<Item = &'a AssignmentPair> + Clone) {
+    let counts = pairs
+        .clone()
+        .fold(Counts::default(), |counts, pair| counts.add(pair));
+
+    println!("pairs: {}", counts.pairs);
+    println!("one fully contains the other: {}", counts.containing);
+    println!("overlapping: {}", counts.overlapping);
+    println!("adjacent: {}", counts.adjacent);
+    println!("overlapping sections: {}", counts.overlapping_sections);
 
-    let answer = strings
-        .into_iter()
-        .flat_map(parse_assignment_pair)
-        .filter(|p| p.has_overlap())
-        .count();
+    let set = IntervalSet::from_assignments(pairs.flat_map(|p| [&p.a, &p.b]));
+    let gaps = set.gaps();
 
-    println!("The answer is {}", answer);
+    println!(
+        "union: {} ranges covering {} sections",
+        set.union().len(),
+        set.covered()
+    );
+    println!(
+        "gaps: {} ranges, {} sections",
+        gaps.len(),
+        gaps.iter()
+            .map(|g| (g.upper - g.lower) as u64 + 1)
+            .sum::<u64>()
+    );
+    for gap in &gaps {
+        println!("  {}-{}", gap.lower, gap.upper);
+    }
+    if let Some(section) = set.busiest_section() {
+        println!(
+            "busiest section: {} with {} elves",
+            section,
+            set.max_depth()
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{parse_assignment_pair, Assignment, AssignmentPair};
+    use std::io::{self, BufRead};
+
+    use crate::{
+        parse_assignment_pair, parse_command, parse_options, read_assignment_pair, Assignment,
+        AssignmentPair, Command, Counts, Options, ParseError, ParseErrorKind,
+    };
+
+    fn range(lower: u32, upper: u32) -> Assignment {
+        Assignment { lower, upper }
+    }
 
     #[test]
-    fn test_consumes() {
-        assert_eq!(
-            Assignment { lower: 2, upper: 8 }.consumes(&Assignment { lower: 3, upper: 7 }),
-            true
-        );
+    fn test_overlaps() {
+        assert!(range(2, 8).overlaps(&range(3, 7)));
+        assert!(range(2, 8).overlaps(&range(3, 9)));
+        assert!(range(5, 7).overlaps(&range(7, 9)));
+        assert!(!range(2, 4).overlaps(&range(6, 8)));
+        assert!(!range(6, 8).overlaps(&range(2, 4)));
+    }
+
+    #[test]
+    fn test_fully_contains() {
+        assert!(range(2, 8).fully_contains(&range(3, 7)));
+        assert!(range(4, 6).fully_contains(&range(6, 6)));
+        assert!(!range(2, 8).fully_contains(&range(3, 9)));
+        assert!(!range(3, 7).fully_contains(&range(2, 8)));
+    }
+
+    #[test]
+    fn test_is_adjacent() {
+        assert!(range(2, 4).is_adjacent(&range(5, 8)));
+        assert!(range(5, 8).is_adjacent(&range(2, 4)));
+        assert!(!range(2, 4).is_adjacent(&range(4, 8)));
+        assert!(!range(2, 4).is_adjacent(&range(6, 8)));
+        assert!(!range(0, u32::MAX).is_adjacent(&range(0, 0)));
+    }
+
+    #[test]
+    fn test_overlap_len() {
+        assert_eq!(range(2, 8).overlap_len(&range(3, 7)), 5);
+        assert_eq!(range(5, 7).overlap_len(&range(7, 9)), 1);
+        assert_eq!(range(2, 4).overlap_len(&range(6, 8)), 0);
+    }
+
+    #[test]
+    fn test_counts() {
+        let example = [
+            "2-4,6-8", "2-3,4-5", "5-7,7-9", "2-8,3-7", "6-6,4-6", "2-6,4-8",
+        ];
+        let counts = example
+            .iter()
+            .map(|l| parse_assignment_pair(0, l).unwrap())
+            .fold(Counts::default(), |counts, pair| counts.add(&pair));
+
         assert_eq!(
-            Assignment { lower: 2, upper: 8 }.consumes(&Assignment { lower: 3, upper: 9 }),
-            true
+            counts,
+            Counts {
+                pairs: 6,
+                containing: 2,
+                overlapping: 4,
+                adjacent: 1,
+                overlapping_sections: 1 + 5 + 1 + 3
+            }
         );
     }
 
@@ -84,8 +376,8 @@ mod tests {
     fn test_parse_assignment_pair() {
         let input = "7-96,6-95".to_string();
         assert_eq!(
-            parse_assignment_pair(input),
-            Some(AssignmentPair {
+            parse_assignment_pair(1, &input),
+            Ok(AssignmentPair {
                 a: Assignment {
                     lower: 7,
                     upper: 96
@@ -97,4 +389,104 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_parse_command() {
+        let args = |xs: &[&str]| Vec::from_iter(xs.iter().map(|s| s.to_string()));
+
+        assert_eq!(parse_command(&args(&[])), Ok(Command::Report));
+        assert_eq!(
+            parse_command(&args(&["touching", "57"])),
+            Ok(Command::Touching(57))
+        );
+        assert_eq!(
+            parse_command(&args(&["overlapping-pairs"])),
+            Ok(Command::OverlappingPairs)
+        );
+        assert_eq!(
+            parse_command(&args(&["touching", "x"])),
+            Err("invalid section for touching: x".to_string())
+        );
+        assert_eq!(
+            parse_command(&args(&["bogus"])),
+            Err("unknown argument: bogus".to_string())
+        );
+        assert!(parse_command(&args(&["touching"])).is_err());
+        assert!(parse_command(&args(&["overlapping-pairs", "3"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |line, kind| Err(ParseError { line, kind });
+
+        assert_eq!(
+            parse_assignment_pair(3, "2-4 6-8"),
+            error(3, ParseErrorKind::MissingComma)
+        );
+        assert_eq!(
+            parse_assignment_pair(4, "2-4,68"),
+            error(
+                4,
+                ParseErrorKind::MissingDash {
+                    assignment: "68".to_string()
+                }
+            )
+        );
+        assert_eq!(
+            parse_assignment_pair(5, "2-x,6-8"),
+            error(
+                5,
+                ParseErrorKind::NonNumericBound {
+                    bound: "x".to_string()
+                }
+            )
+        );
+        assert_eq!(
+            parse_assignment_pair(6, "2-4,9-3"),
+            error(6, ParseErrorKind::InvertedRange { lower: 9, upper: 3 })
+        );
+        assert_eq!(
+            parse_assignment_pair(6, "2-4,9-3").unwrap_err().to_string(),
+            "line 6: range 9-3 ends before it starts"
+        );
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let mut lines = (b"2-4,6-8\n\xff\n" as &[u8]).lines();
+        assert!(read_assignment_pair(1, lines.next().unwrap()).is_ok());
+        assert_eq!(
+            read_assignment_pair(2, lines.next().unwrap()),
+            Err(ParseError {
+                line: 2,
+                kind: ParseErrorKind::InvalidUtf8
+            })
+        );
+        assert_eq!(
+            read_assignment_pair(3, Err(io::Error::other("disk gone")))
+                .unwrap_err()
+                .to_string(),
+            "line 3: cannot read input: disk gone"
+        );
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let args = |xs: &[&str]| Vec::from_iter(xs.iter().map(|s| s.to_string()));
+
+        assert_eq!(
+            parse_options(&args(&["touching", "--skip-invalid", "5"])),
+            Ok(Options {
+                command: Command::Touching(5),
+                skip_invalid: true
+            })
+        );
+        assert_eq!(
+            parse_options(&args(&[])),
+            Ok(Options {
+                command: Command::Report,
+                skip_invalid: false
+            })
+        );
+    }
 }
@@ -1,35 +1,738 @@
-use std::io::{BufRead};
-use std::{fs::File, io};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::BufRead;
+use std::num::IntErrorKind;
+use std::{env, fmt, fs, fs::File, io, process};
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Top,
+    Stats,
+}
+
+#[derive(Debug, PartialEq)]
+enum Format {
+    Table,
+    Json,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Mode {
+    Strict,
+    Lenient,
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    command: Command,
+    k: usize,
+    format: Format,
+    mode: Mode,
+    csv: String,
+    bins: usize,
+}
+
+fn parse_options(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        command: Command::Top,
+        k: 3,
+        format: Format::Table,
+        mode: Mode::Strict,
+        csv: "./elves.csv".to_string(),
+        bins: 10,
+    };
+    let mut args = args;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "top" => options.command = Command::Top,
+            "stats" => options.command = Command::Stats,
+            "--top" => {
+                let value = args.next().ok_or("--top needs a value")?;
+                options.k = value
+                    .parse()
+                    .map_err(|_| format!("invalid value for --top: {}", value))?;
+            }
+            "--json" => options.format = Format::Json,
+            "--table" => options.format = Format::Table,
+            "--csv" => options.csv = args.next().ok_or("--csv needs a value")?,
+            "--bins" => {
+                let value = args.next().ok_or("--bins needs a value")?;
+                options.bins = value
+                    .parse()
+                    .ok()
+                    .filter(|bins| *bins > 0)
+                    .ok_or(format!("invalid value for --bins: {}", value))?;
+            }
+            "--strict" => options.mode = Mode::Strict,
+            "--lenient" => options.mode = Mode::Lenient,
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
 
 fn main() {
+    let options = match parse_options(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("usage: one [top] [--top <k>] [--table | --json] [--strict | --lenient]");
+            eprintln!("       one stats [--csv <path>] [--bins <n>] [--strict | --lenient]");
+            process::exit(2);
+        }
+    };
+
     let file = File::open("./input").unwrap();
     let lines = io::BufReader::new(file).lines();
 
-    let strings = lines.flat_map(|l| l.ok());
-    let grouped_strings = group(strings.collect());
-    let elves: Vec<Vec<i32>> = grouped_strings
-        .iter()
-        .map(|g| g.iter().flat_map(|s| i32::from_str_radix(s, 10).ok()).collect())
-        .collect();
+    let skipped = match options.command {
+        Command::Top => {
+            let report = rank(lines, options.mode, options.k).unwrap_or_else(|e| fail(e));
+
+            match options.format {
+                Format::Table => print!("{}", render_table(&report.ranking)),
+                Format::Json => println!("{}", render_json(&report.ranking)),
+            }
+
+            report.skipped
+        }
+        Command::Stats => {
+            // rows are buffered so a parse error never leaves a partial file behind
+            let mut csv = String::from("elf,total,items\n");
+
+            let (mut totals, skipped) = collect_totals(lines, options.mode, |elf| {
+                csv += &format!("{},{},{}\n", elf.index, elf.total, elf.item_count)
+            })
+            .unwrap_or_else(|e| fail(e));
+
+            if let Err(e) = fs::write(&options.csv, csv) {
+                eprintln!("{}: {}", options.csv, e);
+                process::exit(1);
+            }
+
+            match summarise(&mut totals) {
+                Some(statistics) => {
+                    print!("{}", render_statistics(&statistics));
+                    print!("{}", histogram(&totals, options.bins));
+                }
+                None => println!("no elves in input"),
+            }
+            println!("wrote {}", options.csv);
+
+            skipped
+        }
+    };
+
+    if skipped > 0 {
+        eprintln!("skipped {} malformed lines", skipped);
+    }
+}
+
+fn fail(e: ParseError) -> ! {
+    eprintln!("{}", e);
+    process::exit(1);
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+struct Elf {
+    index: usize,
+    total: u64,
+    item_count: usize,
+}
+
+type RankKey = (u64, Reverse<usize>);
 
-    let mut carrying: Vec<i32> = elves.iter().map(|elf| elf.iter().sum()).collect();
+impl Elf {
+    fn rank_key(&self) -> RankKey {
+        (self.total, Reverse(self.index))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ParseErrorKind {
+    InvalidItem,
+    NegativeItem,
+    MalformedSeparator,
+    Overflow,
+    InvalidUtf8,
+    ReadFailed,
+}
 
-    carrying.sort_by(|a, b| b.cmp(a));
+#[derive(Debug, PartialEq)]
+struct ParseError {
+    line: usize,
+    text: String,
+    kind: ParseErrorKind,
+}
 
-    let answer: i32 = carrying.iter().take(3).sum();
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let what = match self.kind {
+            ParseErrorKind::InvalidItem => "invalid calorie value",
+            ParseErrorKind::NegativeItem => "negative calorie value",
+            ParseErrorKind::MalformedSeparator => "malformed group separator",
+            ParseErrorKind::Overflow => "calorie total overflows at",
+            ParseErrorKind::InvalidUtf8 => "unreadable line,",
+            ParseErrorKind::ReadFailed => "cannot read input,",
+        };
+        write!(f, "line {}: {} {:?}", self.line, what, self.text)
+    }
+}
 
-    println!("the answer is {}", answer);
+struct Elves<I> {
+    lines: I,
+    mode: Mode,
+    index: usize,
+    line: usize,
+    skipped: usize,
+    done: bool,
 }
 
-fn group(vector: Vec<String>) -> Vec<Vec<String>> {
-    vector.into_iter().fold(vec![vec![]], | mut acc, item| {
-        if item.is_empty() {
-            acc.push(Vec::new());
-            acc
+impl<I: Iterator<Item = io::Result<String>>> Iterator for Elves<I> {
+    type Item = Result<Elf, ParseError>;
+
+    fn next(&mut self) -> Option<Result<Elf, ParseError>> {
+        if self.done {
+            return None;
+        }
+
+        let mut elf = Elf {
+            index: self.index,
+            total: 0,
+            item_count: 0,
+        };
+        self.index += 1;
+
+        for read in self.lines.by_ref() {
+            self.line += 1;
+
+            let (text, kind) = match read {
+                Ok(text) if text.is_empty() => return Some(Ok(elf)),
+                Ok(text) if text.trim().is_empty() => (text, ParseErrorKind::MalformedSeparator),
+                Ok(text) => {
+                    let kind = match text.parse::<u64>() {
+                        Ok(calories) => match elf.total.checked_add(calories) {
+                            Some(total) => {
+                                elf.total = total;
+                                elf.item_count += 1;
+                                continue;
+                            }
+                            None => ParseErrorKind::Overflow,
+                        },
+                        Err(e) if *e.kind() == IntErrorKind::PosOverflow => {
+                            ParseErrorKind::Overflow
+                        }
+                        Err(_) if text.starts_with('-') && text[1..].parse::<u64>().is_ok() => {
+                            ParseErrorKind::NegativeItem
+                        }
+                        Err(_) => ParseErrorKind::InvalidItem,
+                    };
+                    (text, kind)
+                }
+                // invalid UTF-8 only spoils its own line, any other read
+                // error ends the input
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                    (e.to_string(), ParseErrorKind::InvalidUtf8)
+                }
+                Err(e) => (e.to_string(), ParseErrorKind::ReadFailed),
+            };
+
+            let fatal = matches!(kind, ParseErrorKind::Overflow | ParseErrorKind::ReadFailed);
+            if self.mode == Mode::Strict || fatal {
+                self.done = true;
+                return Some(Err(ParseError {
+                    line: self.line,
+                    text,
+                    kind,
+                }));
+            }
+
+            self.skipped += 1;
+            if kind == ParseErrorKind::MalformedSeparator {
+                return Some(Ok(elf));
+            }
         }
-        else {
-            acc.last_mut().unwrap().push(item);
-            acc
+
+        self.done = true;
+        Some(Ok(elf))
+    }
+}
+
+fn elves<I: Iterator<Item = io::Result<String>>>(lines: I, mode: Mode) -> Elves<I> {
+    Elves {
+        lines,
+        mode,
+        index: 0,
+        line: 0,
+        skipped: 0,
+        done: false,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Report {
+    ranking: Vec<Elf>,
+    skipped: usize,
+}
+
+fn rank(
+    lines: impl Iterator<Item = io::Result<String>>,
+    mode: Mode,
+    k: usize,
+) -> Result<Report, ParseError> {
+    let mut elves = elves(lines, mode);
+    let mut error = None;
+
+    let ranking = top_k(
+        elves
+            .by_ref()
+            .map_while(|elf| elf.map_err(|e| error = Some(e)).ok())
+            .filter(|elf| elf.item_count > 0),
+        k,
+    );
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(Report {
+            ranking,
+            skipped: elves.skipped,
+        }),
+    }
+}
+
+fn collect_totals(
+    lines: impl Iterator<Item = io::Result<String>>,
+    mode: Mode,
+    mut on_elf: impl FnMut(&Elf),
+) -> Result<(Vec<u64>, usize), ParseError> {
+    let mut elves = elves(lines, mode);
+    let mut totals = Vec::new();
+
+    for elf in elves.by_ref() {
+        let elf = elf?;
+        // empty input, a trailing blank line or a doubled separator all
+        // yield an elf carrying nothing, which is not an elf for the stats
+        if elf.item_count == 0 {
+            continue;
         }
+        on_elf(&elf);
+        totals.push(elf.total);
+    }
+
+    Ok((totals, elves.skipped))
+}
+
+#[derive(Debug, PartialEq)]
+struct Statistics {
+    count: usize,
+    mean: f64,
+    median: f64,
+    p90: u64,
+    p99: u64,
+    min: u64,
+    max: u64,
+}
+
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn summarise(totals: &mut [u64]) -> Option<Statistics> {
+    if totals.is_empty() {
+        return None;
+    }
+    totals.sort_unstable();
+
+    let count = totals.len();
+    let sum: u128 = totals.iter().map(|t| *t as u128).sum();
+    let median = if count.is_multiple_of(2) {
+        (totals[count / 2 - 1] as f64 + totals[count / 2] as f64) / 2.0
+    } else {
+        totals[count / 2] as f64
+    };
+
+    Some(Statistics {
+        count,
+        mean: sum as f64 / count as f64,
+        median,
+        p90: percentile(totals, 90),
+        p99: percentile(totals, 99),
+        min: totals[0],
+        max: totals[count - 1],
     })
 }
+
+fn render_statistics(statistics: &Statistics) -> String {
+    format!(
+        "elves   {}\nmean    {:.2}\nmedian  {:.1}\np90     {}\np99     {}\nmin     {}\nmax     {}\n",
+        statistics.count,
+        statistics.mean,
+        statistics.median,
+        statistics.p90,
+        statistics.p99,
+        statistics.min,
+        statistics.max
+    )
+}
+
+fn histogram(sorted: &[u64], bins: usize) -> String {
+    const WIDTH: usize = 40;
+
+    let (min, max) = match (sorted.first(), sorted.last()) {
+        (Some(min), Some(max)) => (*min, *max),
+        _ => return String::new(),
+    };
+    let span = (max - min) as u128 + 1;
+    let bins = (bins as u128).min(span) as usize;
+
+    let mut counts = vec![0usize; bins];
+    for total in sorted {
+        let bin = ((*total - min) as u128 * bins as u128 / span) as usize;
+        counts[bin] += 1;
+    }
+
+    let tallest = counts.iter().max().copied().unwrap_or(0).max(1);
+    let mut out = String::new();
+    for (bin, count) in counts.iter().enumerate() {
+        let lower = min as u128 + (bin as u128 * span).div_ceil(bins as u128);
+        let upper = min as u128 + ((bin as u128 + 1) * span).div_ceil(bins as u128) - 1;
+        out += &format!(
+            "{:>10} - {:>10} | {:<width$} {}\n",
+            lower,
+            upper,
+            "#".repeat(count * WIDTH / tallest),
+            count,
+            width = WIDTH
+        );
+    }
+    out
+}
+
+fn top_k(elves: impl Iterator<Item = Elf>, k: usize) -> Vec<Elf> {
+    // k comes from the command line, so only reserve up front for small k
+    let mut heap: BinaryHeap<Reverse<(RankKey, Elf)>> =
+        BinaryHeap::with_capacity(k.saturating_add(1).min(1024));
+
+    for elf in elves {
+        let key = elf.rank_key();
+        if heap.len() < k {
+            heap.push(Reverse((key, elf)));
+        } else if heap.peek().is_some_and(|Reverse((min, _))| key > *min) {
+            heap.pop();
+            heap.push(Reverse((key, elf)));
+        }
+    }
+
+    let mut ranking: Vec<Elf> = heap.into_iter().map(|Reverse((_, elf))| elf).collect();
+    ranking.sort_by_key(|elf| Reverse(elf.rank_key()));
+    ranking
+}
+
+fn render_table(ranking: &[Elf]) -> String {
+    let mut out = format!(
+        "{:>4}  {:>6}  {:>10}  {:>5}\n",
+        "rank", "elf", "total", "items"
+    );
+    for (rank, elf) in ranking.iter().enumerate() {
+        out += &format!(
+            "{:>4}  {:>6}  {:>10}  {:>5}\n",
+            rank + 1,
+            elf.index,
+            elf.total,
+            elf.item_count
+        );
+    }
+    let answer: u128 = ranking.iter().map(|elf| elf.total as u128).sum();
+    out += &format!("the answer is {}\n", answer);
+    out
+}
+
+fn render_json(ranking: &[Elf]) -> String {
+    let entries: Vec<String> = ranking
+        .iter()
+        .enumerate()
+        .map(|(rank, elf)| {
+            format!(
+                "{{\"rank\":{},\"elf\":{},\"total\":{},\"items\":{}}}",
+                rank + 1,
+                elf.index,
+                elf.total,
+                elf.item_count
+            )
+        })
+        .collect();
+    format!("[{}]", entries.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, BufRead};
+
+    use crate::{
+        collect_totals, elves, histogram, parse_options, percentile, rank, render_json, summarise,
+        top_k, Command, Elf, Format, Mode, Options, ParseError, ParseErrorKind, Statistics,
+    };
+
+    fn lines(input: &str) -> impl Iterator<Item = io::Result<String>> + '_ {
+        input.lines().map(|l| Ok(l.to_string()))
+    }
+
+    fn args(xs: &[&str]) -> impl Iterator<Item = String> {
+        Vec::from_iter(xs.iter().map(|s| s.to_string())).into_iter()
+    }
+
+    const EXAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+
+    #[test]
+    fn test_elves() {
+        let totals: Vec<(usize, u64, usize)> = elves(lines(EXAMPLE), Mode::Strict)
+            .flatten()
+            .map(|elf| (elf.index, elf.total, elf.item_count))
+            .collect();
+        assert_eq!(
+            totals,
+            vec![
+                (0, 6000, 3),
+                (1, 4000, 1),
+                (2, 11000, 2),
+                (3, 24000, 3),
+                (4, 10000, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_top_k() {
+        let indices = |k| {
+            Vec::from_iter(
+                top_k(elves(lines(EXAMPLE), Mode::Strict).flatten(), k)
+                    .iter()
+                    .map(|e| e.index),
+            )
+        };
+        assert_eq!(indices(3), vec![3, 2, 4]);
+        assert_eq!(indices(1), vec![3]);
+        assert_eq!(indices(0), Vec::<usize>::new());
+        assert_eq!(indices(10), vec![3, 2, 4, 0, 1]);
+        assert_eq!(indices(usize::MAX), vec![3, 2, 4, 0, 1]);
+    }
+
+    #[test]
+    fn test_top_k_ties_rank_by_index() {
+        let indices = |k| {
+            Vec::from_iter(
+                top_k(elves(lines("5\n\n7\n\n5\n\n5"), Mode::Strict).flatten(), k)
+                    .iter()
+                    .map(|e| e.index),
+            )
+        };
+        assert_eq!(indices(2), vec![1, 0]);
+        assert_eq!(indices(3), vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn test_render_json() {
+        let ranking = [Elf {
+            index: 3,
+            total: 24000,
+            item_count: 3,
+        }];
+        assert_eq!(
+            render_json(&ranking),
+            "[{\"rank\":1,\"elf\":3,\"total\":24000,\"items\":3}]"
+        );
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(
+            parse_options(args(&[])),
+            Ok(Options {
+                command: Command::Top,
+                k: 3,
+                format: Format::Table,
+                mode: Mode::Strict,
+                csv: "./elves.csv".to_string(),
+                bins: 10
+            })
+        );
+        assert_eq!(
+            parse_options(args(&["--top", "5", "--json", "--lenient"])),
+            Ok(Options {
+                command: Command::Top,
+                k: 5,
+                format: Format::Json,
+                mode: Mode::Lenient,
+                csv: "./elves.csv".to_string(),
+                bins: 10
+            })
+        );
+        assert!(parse_options(args(&["--top", "x"])).is_err());
+        assert!(parse_options(args(&["--top"])).is_err());
+        assert!(parse_options(args(&["stats", "--bins", "0"])).is_err());
+
+        let stats = parse_options(args(&["stats", "--csv", "out.csv", "--bins", "4"])).unwrap();
+        assert_eq!(stats.command, Command::Stats);
+        assert_eq!(stats.csv, "out.csv");
+        assert_eq!(stats.bins, 4);
+    }
+
+    #[test]
+    fn test_rank_strict_rejects_invalid_item() {
+        assert_eq!(
+            rank(lines("1000\n12a4\n\n3000"), Mode::Strict, 3),
+            Err(ParseError {
+                line: 2,
+                text: "12a4".to_string(),
+                kind: ParseErrorKind::InvalidItem
+            })
+        );
+    }
+
+    #[test]
+    fn test_rank_strict_rejects_whitespace_separator() {
+        assert_eq!(
+            rank(lines("1000\n  \n3000"), Mode::Strict, 3),
+            Err(ParseError {
+                line: 2,
+                text: "  ".to_string(),
+                kind: ParseErrorKind::MalformedSeparator
+            })
+        );
+    }
+
+    #[test]
+    fn test_rank_lenient_counts_skipped() {
+        let report = rank(lines("1000\n12a4\n\n3000\n \n500"), Mode::Lenient, 3).unwrap();
+        assert_eq!(report.skipped, 2);
+        assert_eq!(
+            Vec::from_iter(report.ranking.iter().map(|e| (e.index, e.total))),
+            vec![(1, 3000), (0, 1000), (2, 500)]
+        );
+    }
+
+    #[test]
+    fn test_rank_rejects_negative_item() {
+        assert_eq!(
+            rank(lines("1000\n-20\n\n3000"), Mode::Strict, 3),
+            Err(ParseError {
+                line: 2,
+                text: "-20".to_string(),
+                kind: ParseErrorKind::NegativeItem
+            })
+        );
+        assert_eq!(
+            rank(lines("1000\n-20\n\n3000"), Mode::Lenient, 3)
+                .unwrap()
+                .skipped,
+            1
+        );
+    }
+
+    #[test]
+    fn test_rank_detects_overflow() {
+        let input = format!("{}\n1\n\n5", u64::MAX);
+        let overflow = Err(ParseError {
+            line: 2,
+            text: "1".to_string(),
+            kind: ParseErrorKind::Overflow,
+        });
+        assert_eq!(rank(lines(&input), Mode::Strict, 3), overflow);
+        assert_eq!(rank(lines(&input), Mode::Lenient, 3), overflow);
+
+        let wide = rank(lines(&format!("{}\n\n5", u64::MAX)), Mode::Strict, 1).unwrap();
+        assert_eq!(wide.ranking[0].total, u64::MAX);
+    }
+
+    #[test]
+    fn test_rank_ignores_empty_elves() {
+        assert_eq!(rank(lines(""), Mode::Strict, 3).unwrap().ranking, vec![]);
+
+        let report = rank(lines("5\n\n\n7\n\n"), Mode::Strict, 3).unwrap();
+        assert_eq!(
+            Vec::from_iter(report.ranking.iter().map(|e| (e.index, e.total))),
+            vec![(2, 7), (0, 5)]
+        );
+    }
+
+    #[test]
+    fn test_rank_stops_at_unreadable_line() {
+        let input: &[u8] = b"5\n\xff\n7";
+        let error = rank(input.lines(), Mode::Strict, 3).unwrap_err();
+        assert_eq!((error.line, error.kind), (2, ParseErrorKind::InvalidUtf8));
+
+        let report = rank(input.lines(), Mode::Lenient, 3).unwrap();
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.ranking[0].total, 12);
+
+        let failing = [Ok("5".to_string()), Err(io::Error::other("disk gone"))];
+        let error = rank(failing.into_iter(), Mode::Lenient, 3).unwrap_err();
+        assert_eq!((error.line, error.kind), (2, ParseErrorKind::ReadFailed));
+    }
+
+    #[test]
+    fn test_collect_totals() {
+        let mut seen = Vec::new();
+        let (totals, skipped) =
+            collect_totals(lines(EXAMPLE), Mode::Strict, |elf| seen.push(elf.index)).unwrap();
+        assert_eq!(totals, vec![6000, 4000, 11000, 24000, 10000]);
+        assert_eq!(seen, vec![0, 1, 2, 3, 4]);
+        assert_eq!(skipped, 0);
+    }
+
+    #[test]
+    fn test_collect_totals_ignores_empty_elves() {
+        let mut seen = Vec::new();
+        let (mut totals, _) = collect_totals(lines("5\n\n\n7\n\n"), Mode::Strict, |elf| {
+            seen.push(elf.index)
+        })
+        .unwrap();
+        assert_eq!(totals, vec![5, 7]);
+        assert_eq!(seen, vec![0, 2]);
+        assert_eq!(summarise(&mut totals).unwrap().median, 6.0);
+
+        let (mut totals, _) = collect_totals(lines(""), Mode::Strict, |_| {}).unwrap();
+        assert_eq!(summarise(&mut totals), None);
+    }
+
+    #[test]
+    fn test_summarise() {
+        let mut totals = vec![6000, 4000, 11000, 24000, 10000];
+        assert_eq!(
+            summarise(&mut totals),
+            Some(Statistics {
+                count: 5,
+                mean: 11000.0,
+                median: 10000.0,
+                p90: 24000,
+                p99: 24000,
+                min: 4000,
+                max: 24000
+            })
+        );
+        assert_eq!(summarise(&mut [1, 2, 3, 4]).unwrap().median, 2.5);
+        assert_eq!(summarise(&mut []), None);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let sorted = Vec::from_iter(1..=100);
+        assert_eq!(percentile(&sorted, 90), 90);
+        assert_eq!(percentile(&sorted, 99), 99);
+        assert_eq!(percentile(&[7], 0), 7);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let counts: Vec<String> = histogram(&[0, 1, 2, 3, 9], 2)
+            .lines()
+            .map(|l| l.split_whitespace().last().unwrap().to_string())
+            .collect();
+        assert_eq!(counts, vec!["4", "1"]);
+        assert_eq!(histogram(&[5, 5, 5], 10).lines().count(), 1);
+        assert_eq!(histogram(&[], 10), "");
+    }
+}
@@ -1,47 +1,172 @@
 use std::collections::HashSet;
 use std::io::BufRead;
-use std::{fs::File, io};
+use std::{env, fs::File, io, process};
+
+const PACKET_WINDOW: usize = 4;
+const MESSAGE_WINDOW: usize = 14;
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    // reports both markers when no window is given
+    window: Option<usize>,
+}
+
+fn parse_options(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options { window: None };
+    let mut args = args;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--window" => {
+                let value = args.next().ok_or("--window needs a value")?;
+                options.window = Some(
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|window| *window > 0)
+                        .ok_or(format!("invalid value for --window: {}", value))?,
+                );
+            }
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
 
 fn main() {
+    let options = match parse_options(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("usage: six [--window <n>]");
+            process::exit(2);
+        }
+    };
+
     let file = File::open("./input").unwrap();
     let lines = io::BufReader::new(file).lines();
-    let strings = lines.flat_map(|l| l.ok());
 
-    strings
-        .into_iter()
-        .map(first_marker_offset)
-        .for_each(|offset| println!("{}", offset));
+    let describe = |offset: Option<usize>| offset.map_or("none".to_string(), |o| o.to_string());
+
+    for (i, line) in lines.enumerate() {
+        let line = line.unwrap_or_else(|e| {
+            eprintln!("line {}: cannot read input: {}", i + 1, e);
+            process::exit(1);
+        });
+
+        match options.window {
+            Some(window) => println!(
+                "window {}: marker {}",
+                window,
+                describe(first_marker_offset(&line, window))
+            ),
+            None => println!(
+                "start-of-packet {}, start-of-message {}",
+                describe(first_marker_offset(&line, PACKET_WINDOW)),
+                describe(first_marker_offset(&line, MESSAGE_WINDOW))
+            ),
+        }
+    }
 }
 
-fn number_unqiue(xs: &Vec<char>) -> usize {
-    let set: HashSet<char> = HashSet::from_iter(xs.iter().map(|c| *c));
+fn number_unqiue(xs: &[char]) -> usize {
+    let set: HashSet<char> = HashSet::from_iter(xs.iter().copied());
     set.len()
 }
 
-fn first_marker_offset(s: String) -> usize {
+fn first_marker_offset(s: &str, window: usize) -> Option<usize> {
+    if window == 0 {
+        return None;
+    }
     let mut last_seen: Vec<char> = Vec::new();
 
-    let result = s.char_indices().find(|(_i, c)| {
-        if last_seen.len() == 14 {
+    let result = s.chars().enumerate().find(|(_i, c)| {
+        if last_seen.len() == window {
             last_seen.remove(0);
         }
         last_seen.push(*c);
-        number_unqiue(&last_seen) == 14
+        number_unqiue(&last_seen) == window
     });
 
-    result.unwrap().0 + 1
+    result.map(|(i, _)| i + 1)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::first_marker_offset;
+    use crate::{first_marker_offset, parse_options, Options, MESSAGE_WINDOW, PACKET_WINDOW};
+
+    fn args(xs: &[&str]) -> impl Iterator<Item = String> {
+        Vec::from_iter(xs.iter().map(|s| s.to_string())).into_iter()
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(parse_options(args(&[])), Ok(Options { window: None }));
+        assert_eq!(
+            parse_options(args(&["--window", "5"])),
+            Ok(Options { window: Some(5) })
+        );
+        assert!(parse_options(args(&["--window"])).is_err());
+        assert!(parse_options(args(&["--window", "0"])).is_err());
+        assert!(parse_options(args(&["--window", "x"])).is_err());
+        assert!(parse_options(args(&["--bogus"])).is_err());
+    }
 
     #[test]
     fn test_first_marker_offset() {
-        assert_eq!(first_marker_offset("mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string()), 19);
-        assert_eq!(first_marker_offset("bvwbjplbgvbhsrlpgdmjqwftvncz".to_string()), 23);
-        assert_eq!(first_marker_offset("nppdvjthqldpwncqszvftbrmjlhg".to_string()), 23);
-        assert_eq!(first_marker_offset("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string()), 29);
-        assert_eq!(first_marker_offset("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string()), 26);
+        assert_eq!(
+            first_marker_offset("mjqjpqmgbljsphdztnvjfqwrcgsmlb", MESSAGE_WINDOW),
+            Some(19)
+        );
+        assert_eq!(
+            first_marker_offset("bvwbjplbgvbhsrlpgdmjqwftvncz", MESSAGE_WINDOW),
+            Some(23)
+        );
+        assert_eq!(
+            first_marker_offset("nppdvjthqldpwncqszvftbrmjlhg", MESSAGE_WINDOW),
+            Some(23)
+        );
+        assert_eq!(
+            first_marker_offset("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", MESSAGE_WINDOW),
+            Some(29)
+        );
+        assert_eq!(
+            first_marker_offset("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", MESSAGE_WINDOW),
+            Some(26)
+        );
+    }
+
+    #[test]
+    fn test_first_packet_marker_offset() {
+        assert_eq!(
+            first_marker_offset("mjqjpqmgbljsphdztnvjfqwrcgsmlb", PACKET_WINDOW),
+            Some(7)
+        );
+        assert_eq!(
+            first_marker_offset("bvwbjplbgvbhsrlpgdmjqwftvncz", PACKET_WINDOW),
+            Some(5)
+        );
+        assert_eq!(
+            first_marker_offset("nppdvjthqldpwncqszvftbrmjlhg", PACKET_WINDOW),
+            Some(6)
+        );
+        assert_eq!(
+            first_marker_offset("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", PACKET_WINDOW),
+            Some(10)
+        );
+        assert_eq!(
+            first_marker_offset("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", PACKET_WINDOW),
+            Some(11)
+        );
+    }
+
+    #[test]
+    fn test_no_marker() {
+        assert_eq!(first_marker_offset("abcabc", PACKET_WINDOW), None);
+        assert_eq!(first_marker_offset("abcd", MESSAGE_WINDOW), None);
+        assert_eq!(first_marker_offset("abcd", PACKET_WINDOW), Some(4));
+        assert_eq!(first_marker_offset("", PACKET_WINDOW), None);
+        assert_eq!(first_marker_offset("abcd", 0), None);
     }
 }
@@ -1,67 +1,441 @@
-use std::collections::HashSet;
-use std::io::BufRead;
-use std::{fs::File, io};
+use std::io::{BufRead, Write};
+use std::{env, fmt, fs::File, io, process};
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Mode {
+    Compartments,
+    Groups,
+}
+
+fn parse_modes(s: &str) -> Option<Vec<Mode>> {
+    match s {
+        "compartments" => Some(vec![Mode::Compartments]),
+        "groups" => Some(vec![Mode::Groups]),
+        "both" => Some(vec![Mode::Compartments, Mode::Groups]),
+        _ => None,
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct Options {
+    modes: Vec<Mode>,
+    group_size: usize,
+    allow_partial: bool,
+    export: Option<String>,
+}
+
+fn parse_options(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        modes: parse_modes("both").unwrap(),
+        group_size: 3,
+        allow_partial: false,
+        export: None,
+    };
+    let mut args = args;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--group-size" => {
+                let value = args.next().ok_or("--group-size needs a value")?;
+                options.group_size = value
+                    .parse()
+                    .ok()
+                    .filter(|size| *size > 0)
+                    .ok_or(format!("invalid value for --group-size: {}", value))?;
+            }
+            "--allow-partial" => options.allow_partial = true,
+            "--export" => options.export = Some(args.next().ok_or("--export needs a value")?),
+            _ => options.modes = parse_modes(&arg).ok_or(format!("unknown argument: {}", arg))?,
+        }
+    }
+
+    if options.export.is_some() && !options.modes.contains(&Mode::Groups) {
+        return Err("--export only applies to groups mode".to_string());
+    }
+
+    Ok(options)
+}
 
 fn main() {
-    let file = File::open("./input").unwrap();
-    let lines = io::BufReader::new(file).lines();
+    let options = parse_options(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!(
+            "usage: three [compartments | groups | both] [--group-size <n>] [--allow-partial] [--export <path>]"
+        );
+        process::exit(2);
+    });
+
+    for mode in &options.modes {
+        let file = File::open("./input").unwrap();
+        let lines = io::BufReader::new(file).lines();
+        // a read error ends the input; it is reported in place of an answer
+        // worked out from the lines before it
+        let mut unreadable = None;
+        let strings = lines.enumerate().map_while(|(i, line)| {
+            line.map_err(|e| {
+                unreadable = Some(RucksackError::Unreadable {
+                    line: i + 1,
+                    message: e.to_string(),
+                })
+            })
+            .ok()
+        });
+
+        let (name, answer) = match mode {
+            Mode::Compartments => ("compartments", compartment_priorities(strings)),
+            Mode::Groups => {
+                let (size, allow_partial) = (options.group_size, options.allow_partial);
+                let answer = match &options.export {
+                    None => group_priorities(strings, size, allow_partial),
+                    Some(path) => export_groups(group_badges(strings, size, allow_partial), path),
+                };
+                ("groups", answer)
+            }
+        };
+
+        match unreadable.map_or(answer, Err) {
+            Ok(answer) => println!("The answer ({}) {}", name, answer),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn export_groups(
+    badges: impl Iterator<Item = Result<GroupBadge, RucksackError>>,
+    path: &str,
+) -> Result<u64, RucksackError> {
+    let failed = |e: io::Error| RucksackError::ExportFailed {
+        path: path.to_string(),
+        message: e.to_string(),
+    };
+
+    let mut csv = io::BufWriter::new(File::create(path).map_err(failed)?);
+    writeln!(csv, "first_line,last_line,badge,priority").map_err(failed)?;
+
+    let mut answer = 0;
+    for group in badges {
+        let group = group?;
+        writeln!(
+            csv,
+            "{},{},{},{}",
+            group.lines.first, group.lines.last, group.badge, group.priority
+        )
+        .map_err(failed)?;
+        answer += group.priority as u64;
+    }
+
+    csv.flush().map_err(failed)?;
+    Ok(answer)
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+struct LineRange {
+    first: usize,
+    last: usize,
+}
+
+impl fmt::Display for LineRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.first == self.last {
+            write!(f, "line {}", self.first)
+        } else {
+            write!(f, "lines {}-{}", self.first, self.last)
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+enum RucksackError {
+    UnevenCompartments { line: usize, rucksack: String },
+    NoCommonItem { lines: LineRange },
+    AmbiguousItems { lines: LineRange, items: Vec<char> },
+    IncompleteGroup { lines: LineRange, expected: usize },
+    InvalidItem { line: Option<usize>, item: char },
+    Unreadable { line: usize, message: String },
+    ExportFailed { path: String, message: String },
+}
+
+impl fmt::Display for RucksackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RucksackError::UnevenCompartments { line, rucksack } => write!(
+                f,
+                "line {}: rucksack {:?} cannot be split into two equal compartments",
+                line, rucksack
+            ),
+            RucksackError::NoCommonItem { lines } => write!(f, "{}: no item in common", lines),
+            RucksackError::AmbiguousItems { lines, items } => write!(
+                f,
+                "{}: expected one item in common, found {}: {}",
+                lines,
+                items.len(),
+                String::from_iter(items)
+            ),
+            RucksackError::IncompleteGroup { lines, expected } => write!(
+                f,
+                "{}: trailing group has {} of {} rucksacks (use --allow-partial to accept it)",
+                lines,
+                lines.last - lines.first + 1,
+                expected
+            ),
+            RucksackError::InvalidItem { line, item } => {
+                if let Some(line) = line {
+                    write!(f, "line {}: ", line)?;
+                }
+                write!(f, "{:?} is not an item type (expected a-z or A-Z)", item)
+            }
+            RucksackError::Unreadable { line, message } => {
+                write!(f, "line {}: cannot read rucksack: {}", line, message)
+            }
+            RucksackError::ExportFailed { path, message } => {
+                write!(f, "cannot write {}: {}", path, message)
+            }
+        }
+    }
+}
+
+fn split_compartments(line: &str) -> Option<[&str; 2]> {
+    let middle = line.len() / 2;
+    if !line.len().is_multiple_of(2) || !line.is_char_boundary(middle) {
+        return None;
+    }
+
+    let (first, second) = line.split_at(middle);
+    Some([first, second])
+}
+
+fn compartment_priorities<S: AsRef<str>>(
+    strings: impl IntoIterator<Item = S>,
+) -> Result<u64, RucksackError> {
+    let mut answer = 0;
+
+    for (i, line) in strings.into_iter().enumerate() {
+        let line = line.as_ref();
+        let compartments =
+            split_compartments(line).ok_or_else(|| RucksackError::UnevenCompartments {
+                line: i + 1,
+                rucksack: line.to_string(),
+            })?;
+
+        let lines = LineRange {
+            first: i + 1,
+            last: i + 1,
+        };
+        answer += priority_of(badge(&compartments, lines)?)? as u64;
+    }
+
+    Ok(answer)
+}
+
+#[derive(PartialEq, Debug)]
+struct GroupBadge {
+    lines: LineRange,
+    badge: char,
+    priority: u32,
+}
+
+fn group_badges<S: AsRef<str>>(
+    strings: impl IntoIterator<Item = S>,
+    size: usize,
+    allow_partial: bool,
+) -> impl Iterator<Item = Result<GroupBadge, RucksackError>> {
+    let mut strings = strings.into_iter();
+    let mut group: Vec<S> = Vec::with_capacity(size);
+    let mut first = 1;
+    let mut done = false;
+
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+
+        group.clear();
+        group.extend(strings.by_ref().take(size));
+        if group.is_empty() {
+            return None;
+        }
+
+        let lines = LineRange {
+            first,
+            last: first + group.len() - 1,
+        };
+        first += group.len();
 
-    let strings = lines.flat_map(|l| l.ok());
-    let answer: u32 = 
-        Vec::from_iter(strings)
-            .chunks(3)
-            .map(common_group)
-            .flat_map(|c|c.into_iter().next())
-            .map(priority_of)
-            .sum();
+        let result = if group.len() < size && !allow_partial {
+            Err(RucksackError::IncompleteGroup {
+                lines,
+                expected: size,
+            })
+        } else {
+            badge(&group, lines).and_then(|badge| {
+                Ok(GroupBadge {
+                    lines,
+                    badge,
+                    priority: priority_of(badge)?,
+                })
+            })
+        };
 
-    println!("The answer  {}", answer)
+        done = result.is_err();
+        Some(result)
+    })
 }
 
-fn priority_of(c: char) -> u32 {
+fn group_priorities<S: AsRef<str>>(
+    strings: impl IntoIterator<Item = S>,
+    size: usize,
+    allow_partial: bool,
+) -> Result<u64, RucksackError> {
+    group_badges(strings, size, allow_partial)
+        .map(|group| group.map(|g| g.priority as u64))
+        .sum()
+}
+
+// Compartments of one rucksack share lines.first; rucksacks of a group are
+// numbered on from it.
+fn badge<S: AsRef<str>>(strings: &[S], lines: LineRange) -> Result<char, RucksackError> {
+    let line_of = |i: usize| {
+        if lines.first == lines.last {
+            lines.first
+        } else {
+            lines.first + i
+        }
+    };
+    let common = common_group(strings).map_err(|e| match e {
+        RucksackError::InvalidItem { item, .. } => {
+            let i = strings
+                .iter()
+                .position(|s| Rucksack::parse(s.as_ref()).is_err())
+                .unwrap_or(0);
+            RucksackError::InvalidItem {
+                line: Some(line_of(i)),
+                item,
+            }
+        }
+        e => e,
+    })?;
+
+    match common.len() {
+        1 => Ok(common.items().next().unwrap()),
+        0 => Err(RucksackError::NoCommonItem { lines }),
+        _ => {
+            let mut items = Vec::from_iter(common.items());
+            items.sort_unstable();
+            Err(RucksackError::AmbiguousItems { lines, items })
+        }
+    }
+}
+
+fn priority_of(c: char) -> Result<u32, RucksackError> {
     if c.is_ascii_uppercase() {
-        (c as u32) - ('A' as u32) + 1 + 26
+        Ok((c as u32) - ('A' as u32) + 1 + 26)
+    } else if c.is_ascii_lowercase() {
+        Ok((c as u32) - ('a' as u32) + 1)
     } else {
-        (c as u32) - ('a' as u32) + 1
+        Err(RucksackError::InvalidItem {
+            line: None,
+            item: c,
+        })
     }
 }
 
+fn item_of(priority: u32) -> char {
+    if priority > 26 {
+        char::from(b'A' + (priority - 27) as u8)
+    } else {
+        char::from(b'a' + (priority - 1) as u8)
+    }
+}
 
-fn common_group(strings: &[String]) -> HashSet<char> {
-    let chars: Vec<HashSet<char>> = strings
-        .into_iter()
-        .map(|s| HashSet::from_iter(s.chars()))
-        .collect();
+#[derive(PartialEq, Debug, Clone, Copy)]
+struct Rucksack(u64);
 
-    fn intersect(head: &HashSet<char>, tail: &[HashSet<char>]) -> HashSet<char> {
-        let mut intersection = head.clone();
+impl Rucksack {
+    const ALL: Rucksack = Rucksack(((1 << 53) - 1) & !1);
 
-        tail.iter().for_each(|set| {
-            intersection = HashSet::from_iter(intersection.intersection(set).map(|c| *c)).clone()
-        });
+    fn parse(s: &str) -> Result<Rucksack, RucksackError> {
+        let mut bits = 0;
+
+        for b in s.bytes() {
+            let priority = match b {
+                b'a'..=b'z' => b - b'a' + 1,
+                b'A'..=b'Z' => b - b'A' + 27,
+                _ => {
+                    let item = s.chars().find(|c| !c.is_ascii_alphabetic()).unwrap();
+                    return Err(RucksackError::InvalidItem { line: None, item });
+                }
+            };
+            bits |= 1 << priority;
+        }
+
+        Ok(Rucksack(bits))
+    }
 
-        intersection.clone()
+    fn intersect(self, other: Rucksack) -> Rucksack {
+        Rucksack(self.0 & other.0)
     }
 
-    match chars.split_first() {
-        Some((head, tail)) => intersect(head, tail),
-        None => HashSet::new(),
+    fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    fn items(self) -> impl Iterator<Item = char> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let priority = bits.trailing_zeros();
+            bits &= bits - 1;
+            Some(item_of(priority))
+        })
     }
 }
 
+fn common_group<S: AsRef<str>>(strings: &[S]) -> Result<Rucksack, RucksackError> {
+    if strings.is_empty() {
+        return Ok(Rucksack(0));
+    }
+
+    strings.iter().try_fold(Rucksack::ALL, |common, s| {
+        Ok(common.intersect(Rucksack::parse(s.as_ref())?))
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashSet};
+    use std::collections::HashSet;
 
-    use crate::{common_group, priority_of};
+    use crate::{
+        badge, common_group, compartment_priorities, export_groups, group_badges, group_priorities,
+        item_of, parse_options, priority_of, split_compartments, GroupBadge, LineRange, Mode,
+        Rucksack, RucksackError,
+    };
 
     #[test]
     fn test_priority_of() {
-        assert_eq!(priority_of('a'), 1);
-        assert_eq!(priority_of('A'), 27);
-        assert_eq!(priority_of('p'), 16);
-        assert_eq!(priority_of('L'), 38);
-        assert_eq!(priority_of('t'), 20);
+        assert_eq!(priority_of('a'), Ok(1));
+        assert_eq!(priority_of('A'), Ok(27));
+        assert_eq!(priority_of('p'), Ok(16));
+        assert_eq!(priority_of('L'), Ok(38));
+        assert_eq!(priority_of('t'), Ok(20));
+        assert_eq!(
+            priority_of('1'),
+            Err(RucksackError::InvalidItem {
+                line: None,
+                item: '1'
+            })
+        );
+        assert_eq!(
+            priority_of('é'),
+            Err(RucksackError::InvalidItem {
+                line: None,
+                item: 'é'
+            })
+        );
     }
 
     #[test]
@@ -72,6 +446,202 @@ mod tests {
             "PmmdzqPrVvPwwTWBwg".to_string(),
         ];
 
-        assert_eq!(common_group(&group), HashSet::from_iter(['r']));
+        assert_eq!(
+            common_group(&group).map(|c| HashSet::<char>::from_iter(c.items())),
+            Ok(HashSet::from_iter(['r']))
+        );
+    }
+
+    #[test]
+    fn test_rucksack() {
+        let rucksack = Rucksack::parse("aZza").unwrap();
+        assert_eq!(rucksack.len(), 3);
+        assert_eq!(Vec::from_iter(rucksack.items()), vec!['a', 'z', 'Z']);
+        assert_eq!(Rucksack::ALL.len(), 52);
+        assert_eq!(
+            Rucksack::parse("ab-c"),
+            Err(RucksackError::InvalidItem {
+                line: None,
+                item: '-'
+            })
+        );
+        assert_eq!(
+            Rucksack::parse("abé"),
+            Err(RucksackError::InvalidItem {
+                line: None,
+                item: 'é'
+            })
+        );
+        for c in ('a'..='z').chain('A'..='Z') {
+            assert_eq!(item_of(priority_of(c).unwrap()), c);
+        }
+    }
+
+    const EXAMPLE: [&str; 6] = [
+        "vJrwpWtwJgWrhcsFMMfFFhFp",
+        "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
+        "PmmdzqPrVvPwwTWBwg",
+        "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
+        "ttgJtRGJQctTZtZT",
+        "CrZsJsPPZsGzwwsLwLmpwMDw",
+    ];
+
+    #[test]
+    fn test_split_compartments() {
+        assert_eq!(
+            split_compartments("vJrwpWtwJgWrhcsFMMfFFhFp"),
+            Some(["vJrwpWtwJgWr", "hcsFMMfFFhFp"])
+        );
+        assert_eq!(split_compartments("abc"), None);
+    }
+
+    #[test]
+    fn test_priorities() {
+        assert_eq!(compartment_priorities(EXAMPLE), Ok(157));
+        assert_eq!(group_priorities(EXAMPLE, 3, false), Ok(70));
+        assert_eq!(
+            compartment_priorities(["abc"]),
+            Err(RucksackError::UnevenCompartments {
+                line: 1,
+                rucksack: "abc".to_string()
+            })
+        );
+        assert_eq!(
+            compartment_priorities(["abca", "ab1b"]),
+            Err(RucksackError::InvalidItem {
+                line: Some(2),
+                item: '1'
+            })
+        );
+        assert_eq!(
+            group_priorities(["ab", "bc", "bd", "ab", "a?", "ab"], 3, false)
+                .unwrap_err()
+                .to_string(),
+            "line 5: '?' is not an item type (expected a-z or A-Z)"
+        );
+    }
+
+    #[test]
+    fn test_badge() {
+        let group = ["abcx", "bcdx", "cxyz"];
+        let lines = || LineRange { first: 4, last: 6 };
+
+        assert_eq!(
+            badge(&group, lines()),
+            Err(RucksackError::AmbiguousItems {
+                lines: lines(),
+                items: vec!['c', 'x']
+            })
+        );
+        assert_eq!(
+            badge(&["ab", "cd", "ef"], lines()),
+            Err(RucksackError::NoCommonItem { lines: lines() })
+        );
+        assert_eq!(
+            badge(&["ab", "b1", "c1"], lines()),
+            Err(RucksackError::InvalidItem {
+                line: Some(5),
+                item: '1'
+            })
+        );
+    }
+
+    #[test]
+    fn test_group_error_names_lines() {
+        assert_eq!(
+            group_priorities(["ab", "ac", "ad", "ab", "cd"], 3, true),
+            Err(RucksackError::NoCommonItem {
+                lines: LineRange { first: 4, last: 5 }
+            })
+        );
+        assert_eq!(
+            RucksackError::NoCommonItem {
+                lines: LineRange { first: 4, last: 5 }
+            }
+            .to_string(),
+            "lines 4-5: no item in common"
+        );
+    }
+
+    #[test]
+    fn test_incomplete_group() {
+        assert_eq!(
+            group_priorities(["ab", "ac", "ad", "ab", "bd"], 3, false),
+            Err(RucksackError::IncompleteGroup {
+                lines: LineRange { first: 4, last: 5 },
+                expected: 3
+            })
+        );
+        assert_eq!(
+            group_priorities(["ab", "ac", "ad", "ab", "bd"], 3, true),
+            Ok(1 + 2)
+        );
+    }
+
+    #[test]
+    fn test_group_badges() {
+        let badges: Vec<_> = group_badges(["abx", "cdx", "efY", "Yhg", "z"], 2, false).collect();
+        assert_eq!(
+            badges,
+            vec![
+                Ok(GroupBadge {
+                    lines: LineRange { first: 1, last: 2 },
+                    badge: 'x',
+                    priority: 24
+                }),
+                Ok(GroupBadge {
+                    lines: LineRange { first: 3, last: 4 },
+                    badge: 'Y',
+                    priority: 51
+                }),
+                Err(RucksackError::IncompleteGroup {
+                    lines: LineRange { first: 5, last: 5 },
+                    expected: 2
+                }),
+            ]
+        );
+
+        assert_eq!(
+            group_priorities(EXAMPLE, 1, false),
+            Err(RucksackError::AmbiguousItems {
+                lines: LineRange { first: 1, last: 1 },
+                items: vec!['F', 'J', 'M', 'W', 'c', 'f', 'g', 'h', 'p', 'r', 's', 't', 'v', 'w']
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let args = |xs: &[&str]| Vec::from_iter(xs.iter().map(|s| s.to_string())).into_iter();
+
+        let options = parse_options(args(&[
+            "groups",
+            "--group-size",
+            "4",
+            "--allow-partial",
+            "--export",
+            "out.csv",
+        ]))
+        .unwrap();
+        assert_eq!(options.modes, vec![Mode::Groups]);
+        assert_eq!(options.group_size, 4);
+        assert!(options.allow_partial);
+        assert_eq!(options.export, Some("out.csv".to_string()));
+
+        assert!(parse_options(args(&["--group-size", "0"])).is_err());
+        assert!(parse_options(args(&["bogus"])).is_err());
+        assert!(parse_options(args(&["compartments", "--export", "out.csv"])).is_err());
+        assert!(parse_options(args(&["--export", "out.csv"])).is_ok());
+    }
+
+    #[test]
+    fn test_export_to_bad_path() {
+        let badges = group_badges(EXAMPLE, 3, false);
+        assert_eq!(
+            export_groups(badges, "/nonexistent/groups.csv")
+                .unwrap_err()
+                .to_string(),
+            "cannot write /nonexistent/groups.csv: No such file or directory (os error 2)"
+        );
     }
 }
@@ -0,0 +1,209 @@
+use crate::{parse_move, score, Game, Move};
+
+#[derive(PartialEq, Debug)]
+pub struct RoundAnalysis {
+    pub line: usize,
+    pub their_move: Move,
+    pub optimal_move: Move,
+    pub best: i32,
+    pub worst: i32,
+    pub expected: f64,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Analysis {
+    pub rounds: Vec<RoundAnalysis>,
+    pub unparseable: Vec<(usize, String)>,
+    pub best: i32,
+    pub worst: i32,
+    pub expected: f64,
+}
+
+pub fn parse_distribution(game: &Game, s: &str) -> Result<Vec<f64>, String> {
+    let weights: Vec<f64> = s
+        .split(',')
+        .map(|w| w.trim().parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("invalid distribution: {}", s))?;
+
+    if weights.len() != game.shapes.len() {
+        return Err(format!(
+            "distribution needs {} probabilities, one per shape, found {}",
+            game.shapes.len(),
+            weights.len()
+        ));
+    }
+    if weights.iter().any(|w| !(0.0..=1.0).contains(w)) {
+        return Err(format!("probabilities must be between 0 and 1: {}", s));
+    }
+    if (weights.iter().sum::<f64>() - 1.0).abs() > 1e-6 {
+        return Err(format!("probabilities must sum to 1: {}", s));
+    }
+
+    Ok(weights)
+}
+
+pub fn uniform_distribution(game: &Game) -> Vec<f64> {
+    let n = game.shapes.len();
+    vec![1.0 / n as f64; n]
+}
+
+fn analyse_round(
+    game: &Game,
+    line: usize,
+    their_move: Move,
+    distribution: &[f64],
+) -> RoundAnalysis {
+    let scores: Vec<i32> = (0..game.shapes.len())
+        .map(|i| score(game, their_move, Move(i)))
+        .collect();
+
+    let best = *scores.iter().max().unwrap();
+    let optimal = scores.iter().position(|s| *s == best).unwrap();
+
+    RoundAnalysis {
+        line,
+        their_move,
+        optimal_move: Move(optimal),
+        best,
+        worst: *scores.iter().min().unwrap(),
+        expected: scores
+            .iter()
+            .zip(distribution)
+            .map(|(s, p)| *s as f64 * p)
+            .sum(),
+    }
+}
+
+pub fn analyse<I: Iterator<Item = String>>(
+    game: &Game,
+    lines: I,
+    distribution: &[f64],
+) -> Analysis {
+    let mut rounds = Vec::new();
+    let mut unparseable = Vec::new();
+
+    for (i, text) in lines.enumerate() {
+        match text.split(' ').next().and_then(|s| parse_move(game, s)) {
+            Some(their_move) => rounds.push(analyse_round(game, i + 1, their_move, distribution)),
+            None => unparseable.push((i + 1, text)),
+        }
+    }
+
+    Analysis {
+        best: rounds.iter().map(|r| r.best).sum(),
+        worst: rounds.iter().map(|r| r.worst).sum(),
+        expected: rounds.iter().map(|r| r.expected).sum(),
+        rounds,
+        unparseable,
+    }
+}
+
+pub fn render_analysis(
+    game: &Game,
+    analysis: &Analysis,
+    distribution: &[f64],
+    per_round: bool,
+) -> String {
+    let described: Vec<String> = game
+        .shapes
+        .iter()
+        .zip(distribution)
+        .map(|(shape, p)| format!("{} {:.2}", shape.name, p))
+        .collect();
+
+    let mut out = format!(
+        "best possible score {}\nworst possible score {}\nexpected score ({}) {:.2}\n",
+        analysis.best,
+        analysis.worst,
+        described.join(", "),
+        analysis.expected
+    );
+
+    if per_round {
+        out += &format!(
+            "{:>6}  {:<10}  {:<10}  {:>5}\n",
+            "line", "opponent", "optimal", "score"
+        );
+        for round in &analysis.rounds {
+            out += &format!(
+                "{:>6}  {:<10}  {:<10}  {:>5}\n",
+                round.line,
+                game.name(round.their_move),
+                game.name(round.optimal_move),
+                round.best
+            );
+        }
+    }
+
+    for (line, text) in &analysis.unparseable {
+        out += &format!("{:>6}  unparseable round: {:?}\n", line, text);
+    }
+    if !analysis.unparseable.is_empty() {
+        out += &format!("{} unparseable rounds\n", analysis.unparseable.len());
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{analyse, parse_distribution, render_analysis, uniform_distribution};
+    use crate::{Game, Move};
+
+    const EXAMPLE: [&str; 3] = ["A Y", "B X", "C Z"];
+
+    #[test]
+    fn test_analyse() {
+        let game = Game::standard();
+        let lines = EXAMPLE.iter().map(|l| l.to_string());
+        let analysis = analyse(&game, lines, &[1.0, 0.0, 0.0]);
+
+        assert_eq!(
+            Vec::from_iter(analysis.rounds.iter().map(|r| r.optimal_move)),
+            vec![Move(1), Move(2), Move(0)]
+        );
+        assert_eq!(analysis.best, 8 + 9 + 7);
+        assert_eq!(analysis.worst, 3 + 1 + 2);
+        assert_eq!(analysis.expected, (4 + 1 + 7) as f64);
+    }
+
+    #[test]
+    fn test_uniform_expected() {
+        let game = Game::standard();
+        let lines = ["A Y".to_string()].into_iter();
+        let analysis = analyse(&game, lines, &uniform_distribution(&game));
+
+        assert!((analysis.expected - (4.0 + 8.0 + 3.0) / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unparseable_lines_keep_numbering() {
+        let game = Game::standard();
+        let lines = ["A Y", "Q X", "C Z"].iter().map(|l| l.to_string());
+        let analysis = analyse(&game, lines, &[1.0, 0.0, 0.0]);
+
+        assert_eq!(
+            Vec::from_iter(analysis.rounds.iter().map(|r| r.line)),
+            vec![1, 3]
+        );
+        assert_eq!(analysis.unparseable, vec![(2, "Q X".to_string())]);
+
+        let rendered = render_analysis(&game, &analysis, &[1.0, 0.0, 0.0], true);
+        assert!(rendered.contains("     3  Scissors    Rock            7\n"));
+        assert!(rendered.ends_with("     2  unparseable round: \"Q X\"\n1 unparseable rounds\n"));
+    }
+
+    #[test]
+    fn test_parse_distribution() {
+        let game = Game::standard();
+        assert_eq!(
+            parse_distribution(&game, "0.5,0.25,0.25"),
+            Ok(vec![0.5, 0.25, 0.25])
+        );
+        assert!(parse_distribution(&game, "0.5,0.5").is_err());
+        assert!(parse_distribution(&game, "0.5,0.5,0.5").is_err());
+        assert!(parse_distribution(&game, "1.5,-0.5,0").is_err());
+        assert!(parse_distribution(&game, "a,b,c").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{parse_line, Game, Interpretation, Move};
+
+#[derive(PartialEq, Debug)]
+pub enum AuditEntry {
+    Round {
+        line: usize,
+        their_move: Move,
+        your_move: Move,
+        shape_score: i32,
+        outcome_score: i32,
+        total: i32,
+    },
+    Unparseable {
+        line: usize,
+        text: String,
+    },
+}
+
+pub fn audit<I: Iterator<Item = String>>(
+    game: &Game,
+    lines: I,
+    interpretation: Interpretation,
+) -> Vec<AuditEntry> {
+    let mut total = 0;
+
+    lines
+        .enumerate()
+        .map(|(i, text)| match parse_line(game, &text, interpretation) {
+            Some((their_move, your_move)) => {
+                let shape_score = game.shapes[your_move.0].score;
+                let outcome_score = game.points(game.outcome(their_move, your_move));
+                total += shape_score + outcome_score;
+
+                AuditEntry::Round {
+                    line: i + 1,
+                    their_move,
+                    your_move,
+                    shape_score,
+                    outcome_score,
+                    total,
+                }
+            }
+            None => AuditEntry::Unparseable { line: i + 1, text },
+        })
+        .collect()
+}
+
+pub fn render_table(game: &Game, entries: &[AuditEntry]) -> String {
+    let mut out = format!(
+        "{:>6}  {:<10}  {:<10}  {:>5}  {:>7}  {:>7}\n",
+        "line", "opponent", "chosen", "shape", "outcome", "total"
+    );
+    let mut unparseable = 0;
+
+    for entry in entries {
+        out += &match entry {
+            AuditEntry::Round {
+                line,
+                their_move,
+                your_move,
+                shape_score,
+                outcome_score,
+                total,
+            } => format!(
+                "{:>6}  {:<10}  {:<10}  {:>5}  {:>7}  {:>7}\n",
+                line,
+                game.name(*their_move),
+                game.name(*your_move),
+                shape_score,
+                outcome_score,
+                total
+            ),
+            AuditEntry::Unparseable { line, text } => {
+                unparseable += 1;
+                format!("{:>6}  unparseable round: {:?}\n", line, text)
+            }
+        };
+    }
+
+    if unparseable > 0 {
+        out += &format!("{} unparseable rounds\n", unparseable);
+    }
+    out
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            c if (c as u32) < 0x20 => out += &format!("\\u{:04x}", c as u32),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+pub fn render_json_lines(game: &Game, entries: &[AuditEntry]) -> String {
+    let mut out = String::new();
+
+    for entry in entries {
+        out += &match entry {
+            AuditEntry::Round {
+                line,
+                their_move,
+                your_move,
+                shape_score,
+                outcome_score,
+                total,
+            } => format!(
+                "{{\"line\":{},\"opponent\":{},\"chosen\":{},\"shape_score\":{},\"outcome_score\":{},\"total\":{}}}\n",
+                line,
+                json_string(game.name(*their_move)),
+                json_string(game.name(*your_move)),
+                shape_score,
+                outcome_score,
+                total
+            ),
+            AuditEntry::Unparseable { line, text } => format!(
+                "{{\"line\":{},\"error\":\"unparseable round\",\"text\":{}}}\n",
+                line,
+                json_string(text)
+            ),
+        };
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{audit, json_string, render_json_lines, AuditEntry};
+    use crate::{Game, Interpretation, Move};
+
+    #[test]
+    fn test_audit() {
+        let game = Game::standard();
+        let lines = ["A Y", "B Q", "C Z"].iter().map(|l| l.to_string());
+        let entries = audit(&game, lines, Interpretation::WantedOutcome);
+
+        assert_eq!(
+            entries,
+            vec![
+                AuditEntry::Round {
+                    line: 1,
+                    their_move: Move(0),
+                    your_move: Move(0),
+                    shape_score: 1,
+                    outcome_score: 3,
+                    total: 4
+                },
+                AuditEntry::Unparseable {
+                    line: 2,
+                    text: "B Q".to_string()
+                },
+                AuditEntry::Round {
+                    line: 3,
+                    their_move: Move(2),
+                    your_move: Move(0),
+                    shape_score: 1,
+                    outcome_score: 6,
+                    total: 11
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_json_lines() {
+        let game = Game::standard();
+        let lines = ["A X", "\"oops\""].iter().map(|l| l.to_string());
+        let entries = audit(&game, lines, Interpretation::YourMove);
+
+        assert_eq!(
+            render_json_lines(&game, &entries),
+            "{\"line\":1,\"opponent\":\"Rock\",\"chosen\":\"Rock\",\"shape_score\":1,\"outcome_score\":3,\"total\":4}\n\
+             {\"line\":2,\"error\":\"unparseable round\",\"text\":\"\\\"oops\\\"\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a\\b\tc"), "\"a\\\\b\\u0009c\"");
+    }
+}
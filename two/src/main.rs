@@ -1,97 +1,583 @@
+mod analysis;
+mod audit;
+mod tournament;
+
 use std::io::BufRead;
-use std::{fs::File, io};
+use std::{env, fs, fs::File, io, process};
 
-#[derive(PartialEq, Copy, Clone)]
-enum Move {
-    Rock,
-    Paper,
-    Scissors,
-}
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
+struct Move(usize);
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Debug, Copy, Clone)]
 enum Outcome {
     Lose,
     Draw,
     Win,
 }
 
-fn your_move(their_move: Move, outcome_wanted: Outcome) -> Move {
+#[derive(PartialEq, Debug, Clone)]
+struct Shape {
+    name: String,
+    their_letter: String,
+    your_letter: String,
+    score: i32,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+struct Game {
+    shapes: Vec<Shape>,
+    outcome_letters: [String; 3],
+    lose_points: i32,
+    draw_points: i32,
+    win_points: i32,
+}
+
+impl Game {
+    fn standard() -> Game {
+        parse_game(STANDARD_RULES).unwrap()
+    }
+
+    fn outcome(&self, their_move: Move, your_move: Move) -> Outcome {
+        let n = self.shapes.len();
+        let difference = (your_move.0 + n - their_move.0) % n;
+
+        if difference == 0 {
+            Outcome::Draw
+        } else if difference % 2 == 1 {
+            Outcome::Win
+        } else {
+            Outcome::Lose
+        }
+    }
+
+    fn points(&self, outcome: Outcome) -> i32 {
+        match outcome {
+            Outcome::Lose => self.lose_points,
+            Outcome::Draw => self.draw_points,
+            Outcome::Win => self.win_points,
+        }
+    }
+
+    fn name(&self, mv: Move) -> &str {
+        &self.shapes[mv.0].name
+    }
+}
+
+const STANDARD_RULES: &str = "\
+shape Rock A X 1
+shape Paper B Y 2
+shape Scissors C Z 3
+outcomes X Y Z
+points 0 3 6
+";
+
+fn parse_game(rules: &str) -> Result<Game, String> {
+    let mut shapes: Vec<Shape> = Vec::new();
+    let mut outcome_letters = ["X", "Y", "Z"].map(String::from);
+    let mut points = [0, 3, 6];
+
+    for (number, line) in rules.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let error = |what: &str| format!("line {}: {}: {:?}", number + 1, what, line);
+
+        match fields.as_slice() {
+            [] => {}
+            [comment, ..] if comment.starts_with('#') => {}
+            ["shape", name, their_letter, your_letter, score] => shapes.push(Shape {
+                name: name.to_string(),
+                their_letter: their_letter.to_string(),
+                your_letter: your_letter.to_string(),
+                score: score.parse().map_err(|_| error("invalid shape score"))?,
+            }),
+            ["outcomes", lose, draw, win] => {
+                outcome_letters = [lose, draw, win].map(|s| s.to_string())
+            }
+            ["points", lose, draw, win] => {
+                for (point, value) in points.iter_mut().zip([lose, draw, win]) {
+                    *point = value.parse().map_err(|_| error("invalid points"))?;
+                }
+            }
+            _ => return Err(error("unrecognised rule")),
+        }
+    }
+
+    if shapes.len() < 3 || shapes.len().is_multiple_of(2) {
+        return Err(format!(
+            "a cyclic game needs an odd number of shapes, at least 3, found {}",
+            shapes.len()
+        ));
+    }
+
+    for (i, shape) in shapes.iter().enumerate() {
+        let duplicate = shapes[..i].iter().any(|other| {
+            other.their_letter == shape.their_letter || other.your_letter == shape.your_letter
+        });
+        if duplicate {
+            return Err(format!("shape {} reuses an input letter", shape.name));
+        }
+    }
+
+    let [lose_points, draw_points, win_points] = points;
+    Ok(Game {
+        shapes,
+        outcome_letters,
+        lose_points,
+        draw_points,
+        win_points,
+    })
+}
+
+fn your_move(game: &Game, their_move: Move, outcome_wanted: Outcome) -> Move {
+    let n = game.shapes.len();
     match outcome_wanted {
         Outcome::Draw => their_move,
-        Outcome::Lose => match their_move {
-            Move::Rock => Move::Scissors,
-            Move::Paper => Move::Rock,
-            Move::Scissors => Move::Paper,
-        },
-        Outcome::Win => match their_move {
-            Move::Rock => Move::Paper,
-            Move::Paper => Move::Scissors,
-            Move::Scissors => Move::Rock,
-        },
-    }
-}
-
-fn score(their_move: Move, your_move: Move) -> i32 {
-    let your_shape_score = match &your_move {
-        Move::Rock => 1,
-        Move::Paper => 2,
-        Move::Scissors => 3,
-    };
+        Outcome::Lose => Move((their_move.0 + n - 1) % n),
+        Outcome::Win => Move((their_move.0 + 1) % n),
+    }
+}
 
-    let outcome = match your_move {
-        Move::Rock if their_move == Move::Scissors => 6,
-        Move::Rock if their_move == Move::Paper => 0,
+fn score(game: &Game, their_move: Move, your_move: Move) -> i32 {
+    let your_shape_score = game.shapes[your_move.0].score;
+    let outcome = game.points(game.outcome(their_move, your_move));
 
-        Move::Paper if their_move == Move::Rock => 6,
-        Move::Paper if their_move == Move::Scissors => 0,
+    your_shape_score + outcome
+}
 
-        Move::Scissors if their_move == Move::Paper => 6,
-        Move::Scissors if their_move == Move::Rock => 0,
+fn parse_move(game: &Game, s: &str) -> Option<Move> {
+    game.shapes
+        .iter()
+        .position(|shape| shape.their_letter == s)
+        .map(Move)
+}
 
-        _ => 3,
-    };
+fn parse_outcome(game: &Game, s: &str) -> Option<Outcome> {
+    game.outcome_letters
+        .iter()
+        .position(|letter| letter == s)
+        .map(|i| [Outcome::Lose, Outcome::Draw, Outcome::Win][i])
+}
 
-    your_shape_score + outcome
+fn parse_your_move(game: &Game, s: &str) -> Option<Move> {
+    game.shapes
+        .iter()
+        .position(|shape| shape.your_letter == s)
+        .map(Move)
 }
 
-fn parse_move(s: &&str) -> Option<Move> {
-    match *s {
-        "A" => Some(Move::Rock),
-        "B" => Some(Move::Paper),
-        "C" => Some(Move::Scissors),
-        _ => None,
+#[derive(PartialEq, Debug, Copy, Clone)]
+enum Interpretation {
+    YourMove,
+    WantedOutcome,
+}
+
+impl Interpretation {
+    fn describe(&self) -> &'static str {
+        match self {
+            Interpretation::YourMove => "second column is your move",
+            Interpretation::WantedOutcome => "second column is the wanted outcome",
+        }
     }
 }
 
-fn parse_outcome(s: &&str) -> Option<Outcome> {
-    match *s {
-        "X" => Some(Outcome::Lose),
-        "Y" => Some(Outcome::Draw),
-        "Z" => Some(Outcome::Win),
+fn parse_interpretations(s: &str) -> Option<Vec<Interpretation>> {
+    match s {
+        "move" => Some(vec![Interpretation::YourMove]),
+        "outcome" => Some(vec![Interpretation::WantedOutcome]),
+        "both" => Some(vec![
+            Interpretation::YourMove,
+            Interpretation::WantedOutcome,
+        ]),
         _ => None,
     }
 }
 
-fn parse_line(line: String) -> Option<(Move, Outcome)> {
+fn parse_line(game: &Game, line: &str, interpretation: Interpretation) -> Option<(Move, Move)> {
     let parts: Vec<&str> = line.split(' ').collect();
-    let mv = parts.get(0).and_then(parse_move);
-    let outcome = parts.get(1).and_then(parse_outcome);
-    mv.zip(outcome)
+    let their_move = parts.first().and_then(|s| parse_move(game, s));
+    let second = parts.get(1);
+
+    let your_move = match interpretation {
+        Interpretation::YourMove => second.and_then(|s| parse_your_move(game, s)),
+        Interpretation::WantedOutcome => second
+            .and_then(|s| parse_outcome(game, s))
+            .zip(their_move)
+            .map(|(outcome, their_move)| your_move(game, their_move, outcome)),
+    };
+
+    their_move.zip(your_move)
+}
+
+// Lines are read as bytes so one that is not valid UTF-8 still comes through,
+// lossily decoded, as an unparseable round rather than ending the guide.
+fn guide_lines(reader: impl BufRead) -> impl Iterator<Item = String> {
+    reader.split(b'\n').enumerate().map(|(i, line)| match line {
+        Ok(mut bytes) => {
+            if bytes.last() == Some(&b'\r') {
+                bytes.pop();
+            }
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+        Err(e) => {
+            eprintln!("line {}: cannot read input: {}", i + 1, e);
+            process::exit(1);
+        }
+    })
+}
+
+fn total_scores<I: Iterator<Item = String>>(
+    game: &Game,
+    lines: I,
+    interpretations: &[Interpretation],
+) -> Vec<(i32, usize)> {
+    let mut totals = vec![(0, 0); interpretations.len()];
+
+    for line in lines {
+        for ((total, unparseable), interpretation) in totals.iter_mut().zip(interpretations) {
+            match parse_line(game, &line, *interpretation) {
+                Some((them, you)) => *total += score(game, them, you),
+                None => *unparseable += 1,
+            }
+        }
+    }
+
+    totals
+}
+
+#[derive(PartialEq, Debug)]
+enum Command {
+    Score(Vec<Interpretation>),
+    Tournament {
+        rounds: usize,
+        entrants: Vec<String>,
+    },
+    Analyse {
+        distribution: Option<String>,
+        per_round: bool,
+    },
+    Audit {
+        interpretation: Interpretation,
+        json: bool,
+    },
+}
+
+#[derive(PartialEq, Debug)]
+struct Options {
+    command: Command,
+    rules: Option<String>,
+}
+
+fn parse_options(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        command: Command::Score(parse_interpretations("both").unwrap()),
+        rules: None,
+    };
+    let mut args = args;
+
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), &mut options.command) {
+            ("--rules", _) => options.rules = Some(args.next().ok_or("--rules needs a value")?),
+            ("tournament", _) => {
+                options.command = Command::Tournament {
+                    rounds: 1000,
+                    entrants: Vec::new(),
+                }
+            }
+            ("analyse", _) => {
+                options.command = Command::Analyse {
+                    distribution: None,
+                    per_round: true,
+                }
+            }
+            ("--distribution", Command::Analyse { distribution, .. }) => {
+                *distribution = Some(args.next().ok_or("--distribution needs a value")?)
+            }
+            ("--summary", Command::Analyse { per_round, .. }) => *per_round = false,
+            ("audit", _) => {
+                options.command = Command::Audit {
+                    interpretation: Interpretation::WantedOutcome,
+                    json: false,
+                }
+            }
+            ("--json", Command::Audit { json, .. }) => *json = true,
+            ("--table", Command::Audit { json, .. }) => *json = false,
+            ("move", Command::Audit { interpretation, .. }) => {
+                *interpretation = Interpretation::YourMove
+            }
+            ("outcome", Command::Audit { interpretation, .. }) => {
+                *interpretation = Interpretation::WantedOutcome
+            }
+            ("--rounds", Command::Tournament { rounds, .. }) => {
+                let value = args.next().ok_or("--rounds needs a value")?;
+                *rounds = value
+                    .parse()
+                    .map_err(|_| format!("invalid value for --rounds: {}", value))?;
+            }
+            (_, Command::Tournament { entrants, .. }) if !arg.starts_with("--") => {
+                entrants.push(arg)
+            }
+            (_, Command::Score(interpretations)) => {
+                *interpretations =
+                    parse_interpretations(&arg).ok_or(format!("unknown argument: {}", arg))?
+            }
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+
+    Ok(options)
 }
 
 fn main() {
-    let file = File::open("./input").unwrap();
-    let lines = io::BufReader::new(file).lines();
+    let options = parse_options(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!("usage: two [move | outcome | both] [--rules <path>]");
+        eprintln!("       two tournament [--rounds <n>] [strategy...] [--rules <path>]");
+        eprintln!("       two analyse [--distribution <p1,p2,...>] [--summary] [--rules <path>]");
+        eprintln!("       two audit [move | outcome] [--table | --json] [--rules <path>]");
+        eprintln!("strategies: always:<shape>, copy, frequency, random:<seed>");
+        process::exit(2);
+    });
+
+    let game = match &options.rules {
+        None => Game::standard(),
+        Some(path) => fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|rules| parse_game(&rules))
+            .unwrap_or_else(|e| {
+                eprintln!("{}: {}", path, e);
+                process::exit(1);
+            }),
+    };
+
+    match options.command {
+        Command::Score(interpretations) => {
+            let strings = guide_lines(io::BufReader::new(File::open("./input").unwrap()));
+            let answers = total_scores(&game, strings, &interpretations);
+
+            for (interpretation, (answer, unparseable)) in interpretations.iter().zip(answers) {
+                println!("The answer ({}) is {}", interpretation.describe(), answer);
+                if unparseable > 0 {
+                    eprintln!(
+                        "skipped {} unparseable rounds ({}), see the audit command",
+                        unparseable,
+                        interpretation.describe()
+                    );
+                }
+            }
+        }
+        Command::Tournament { rounds, entrants } => {
+            let entrants = if entrants.is_empty() {
+                tournament::default_entrants(&game)
+            } else {
+                entrants
+            };
+
+            match tournament::round_robin(&game, &entrants, rounds) {
+                Ok(standings) => print!("{}", tournament::render_leaderboard(&standings)),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Analyse {
+            distribution,
+            per_round,
+        } => {
+            let distribution = match distribution {
+                None => analysis::uniform_distribution(&game),
+                Some(s) => analysis::parse_distribution(&game, &s).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }),
+            };
+
+            let strings = guide_lines(io::BufReader::new(File::open("./input").unwrap()));
+            let result = analysis::analyse(&game, strings, &distribution);
+
+            print!(
+                "{}",
+                analysis::render_analysis(&game, &result, &distribution, per_round)
+            );
+        }
+        Command::Audit {
+            interpretation,
+            json,
+        } => {
+            let strings = guide_lines(io::BufReader::new(File::open("./input").unwrap()));
+            let entries = audit::audit(&game, strings, interpretation);
+
+            if json {
+                print!("{}", audit::render_json_lines(&game, &entries));
+            } else {
+                print!("{}", audit::render_table(&game, &entries));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        guide_lines, parse_game, parse_line, parse_options, score, total_scores, your_move,
+        Command, Game, Interpretation, Move, Outcome,
+    };
+
+    const EXAMPLE: [&str; 3] = ["A Y", "B X", "C Z"];
+
+    const ROCK: Move = Move(0);
+    const PAPER: Move = Move(1);
+    const SCISSORS: Move = Move(2);
+
+    const RPSLS: &str = "\
+# Rock Paper Scissors Spock Lizard, in cyclic order
+shape Rock A V 1
+shape Paper B W 2
+shape Scissors C X 3
+shape Spock D Y 4
+shape Lizard E Z 5
+outcomes L D W
+points 0 3 6
+";
+
+    #[test]
+    fn test_score() {
+        let game = Game::standard();
+        assert_eq!(score(&game, ROCK, PAPER), 8);
+        assert_eq!(score(&game, PAPER, ROCK), 1);
+        assert_eq!(score(&game, SCISSORS, SCISSORS), 6);
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let game = Game::standard();
+        assert_eq!(
+            parse_line(&game, "A Y", Interpretation::YourMove),
+            Some((ROCK, PAPER))
+        );
+        assert_eq!(
+            parse_line(&game, "A Y", Interpretation::WantedOutcome),
+            Some((ROCK, ROCK))
+        );
+        assert_eq!(parse_line(&game, "A Q", Interpretation::YourMove), None);
+    }
 
-    let strings = lines.flat_map(|l| l.ok());
-    let move_and_wanted_outcome = strings.flat_map(parse_line);
+    #[test]
+    fn test_guide_lines_keep_unreadable_lines() {
+        let input: &[u8] = b"A Y\r\nB \xff\nC Z\n";
+        let lines = Vec::from_iter(guide_lines(input));
 
-    let moves = move_and_wanted_outcome
-        .map(|(their_move, wanted_outcome)| (their_move, your_move(their_move, wanted_outcome)));
+        assert_eq!(lines, vec!["A Y", "B \u{fffd}", "C Z"]);
+        assert_eq!(
+            total_scores(
+                &Game::standard(),
+                lines.into_iter(),
+                &[Interpretation::YourMove]
+            ),
+            vec![(8 + 6, 1)]
+        );
+    }
+
+    #[test]
+    fn test_total_scores() {
+        let lines = EXAMPLE.iter().map(|l| l.to_string());
+        assert_eq!(
+            total_scores(
+                &Game::standard(),
+                lines,
+                &[Interpretation::YourMove, Interpretation::WantedOutcome]
+            ),
+            vec![(15, 0), (12, 0)]
+        );
+
+        let disputed = ["A Y", "B Q", "", "C Z"].iter().map(|l| l.to_string());
+        assert_eq!(
+            total_scores(&Game::standard(), disputed, &[Interpretation::YourMove]),
+            vec![(8 + 6, 2)]
+        );
+    }
 
-    let scores = moves.map(|(them, you)| score(them, you));
+    #[test]
+    fn test_rpsls_outcomes() {
+        let game = parse_game(RPSLS).unwrap();
+        let beats = [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ];
+        let named = |name| Move(game.shapes.iter().position(|s| s.name == name).unwrap());
 
-    let answer: i32 = scores.sum();
+        for (winner, loser) in beats {
+            assert_eq!(game.outcome(named(loser), named(winner)), Outcome::Win);
+            assert_eq!(game.outcome(named(winner), named(loser)), Outcome::Lose);
+        }
+    }
 
-    println!("The answer is {}", answer);
+    #[test]
+    fn test_rpsls_your_move() {
+        let game = parse_game(RPSLS).unwrap();
+        for their_move in (0..5).map(Move) {
+            for outcome in [Outcome::Lose, Outcome::Draw, Outcome::Win] {
+                let mine = your_move(&game, their_move, outcome);
+                assert_eq!(game.outcome(their_move, mine), outcome);
+            }
+        }
+        assert_eq!(
+            parse_line(&game, "D V", Interpretation::YourMove),
+            Some((Move(3), Move(0)))
+        );
+    }
+
+    #[test]
+    fn test_parse_game_rejects_bad_rules() {
+        assert!(parse_game("shape Rock A X 1\nshape Paper B Y 2\n").is_err());
+        assert!(parse_game("shape Rock A X one\n").is_err());
+        assert!(parse_game("shape Rock A X 1\nshape Paper A Y 2\nshape Scissors C Z 3\n").is_err());
+        assert!(parse_game("bogus\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let args = |xs: &[&str]| Vec::from_iter(xs.iter().map(|s| s.to_string())).into_iter();
+
+        assert_eq!(
+            parse_options(args(&["move"])).unwrap().command,
+            Command::Score(vec![Interpretation::YourMove])
+        );
+        assert_eq!(
+            parse_options(args(&["tournament", "--rounds", "5", "copy", "random:3"]))
+                .unwrap()
+                .command,
+            Command::Tournament {
+                rounds: 5,
+                entrants: vec!["copy".to_string(), "random:3".to_string()]
+            }
+        );
+        assert_eq!(
+            parse_options(args(&["analyse", "--distribution", "1,0,0", "--summary"]))
+                .unwrap()
+                .command,
+            Command::Analyse {
+                distribution: Some("1,0,0".to_string()),
+                per_round: false
+            }
+        );
+        assert_eq!(
+            parse_options(args(&["audit", "move", "--json"]))
+                .unwrap()
+                .command,
+            Command::Audit {
+                interpretation: Interpretation::YourMove,
+                json: true
+            }
+        );
+        assert!(parse_options(args(&["--rounds", "5"])).is_err());
+        assert!(parse_options(args(&["tournament", "--bogus"])).is_err());
+    }
 }
@@ -0,0 +1,303 @@
+use crate::{score, your_move, Game, Move, Outcome};
+
+pub trait Strategy {
+    fn choose(&mut self, game: &Game) -> Move;
+
+    fn observe(&mut self, _your_move: Move, _their_move: Move, _outcome: Outcome) {}
+}
+
+struct Always(Move);
+
+impl Strategy for Always {
+    fn choose(&mut self, _game: &Game) -> Move {
+        self.0
+    }
+}
+
+struct CopyLast {
+    last: Option<Move>,
+}
+
+impl Strategy for CopyLast {
+    fn choose(&mut self, _game: &Game) -> Move {
+        self.last.unwrap_or(Move(0))
+    }
+
+    fn observe(&mut self, _your_move: Move, their_move: Move, _outcome: Outcome) {
+        self.last = Some(their_move);
+    }
+}
+
+struct FrequencyCounter {
+    seen: Vec<usize>,
+}
+
+impl Strategy for FrequencyCounter {
+    fn choose(&mut self, game: &Game) -> Move {
+        let most_frequent = self
+            .seen
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .max_by_key(|(i, count)| (**count, std::cmp::Reverse(*i)))
+            .map(|(i, _)| Move(i));
+
+        match most_frequent {
+            Some(their_move) => your_move(game, their_move, Outcome::Win),
+            None => Move(0),
+        }
+    }
+
+    fn observe(&mut self, _your_move: Move, their_move: Move, _outcome: Outcome) {
+        self.seen[their_move.0] += 1;
+    }
+}
+
+struct Random {
+    state: u64,
+}
+
+impl Random {
+    fn new(seed: u64) -> Random {
+        Random {
+            state: seed ^ 0x9e37_79b9_7f4a_7c15,
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        // splitmix64
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl Strategy for Random {
+    fn choose(&mut self, game: &Game) -> Move {
+        Move((self.next_u64() % game.shapes.len() as u64) as usize)
+    }
+}
+
+pub fn parse_strategy(game: &Game, spec: &str) -> Result<Box<dyn Strategy>, String> {
+    let (kind, argument) = match spec.split_once(':') {
+        Some((kind, argument)) => (kind, Some(argument)),
+        None => (spec, None),
+    };
+
+    match (kind, argument) {
+        ("always", Some(name)) => game
+            .shapes
+            .iter()
+            .position(|shape| shape.name.eq_ignore_ascii_case(name))
+            .map(|i| Box::new(Always(Move(i))) as Box<dyn Strategy>)
+            .ok_or(format!("unknown shape in strategy {}", spec)),
+        ("copy", None) => Ok(Box::new(CopyLast { last: None })),
+        ("frequency", None) => Ok(Box::new(FrequencyCounter {
+            seen: vec![0; game.shapes.len()],
+        })),
+        ("random", Some(seed)) => seed
+            .parse()
+            .map(|seed| Box::new(Random::new(seed)) as Box<dyn Strategy>)
+            .map_err(|_| format!("invalid seed in strategy {}", spec)),
+        _ => Err(format!("unknown strategy {}", spec)),
+    }
+}
+
+pub fn default_entrants(game: &Game) -> Vec<String> {
+    vec![
+        format!("always:{}", game.shapes[0].name),
+        "copy".to_string(),
+        "frequency".to_string(),
+        "random:1".to_string(),
+    ]
+}
+
+fn play_match(
+    game: &Game,
+    a: &mut dyn Strategy,
+    b: &mut dyn Strategy,
+    rounds: usize,
+) -> Result<(i64, i64), String> {
+    let mut totals: (i64, i64) = (0, 0);
+    let overflow = |round| format!("points overflow in round {}", round);
+
+    for round in 1..=rounds {
+        let a_move = a.choose(game);
+        let b_move = b.choose(game);
+
+        a.observe(a_move, b_move, game.outcome(b_move, a_move));
+        b.observe(b_move, a_move, game.outcome(a_move, b_move));
+
+        totals.0 = totals
+            .0
+            .checked_add(score(game, b_move, a_move) as i64)
+            .ok_or_else(|| overflow(round))?;
+        totals.1 = totals
+            .1
+            .checked_add(score(game, a_move, b_move) as i64)
+            .ok_or_else(|| overflow(round))?;
+    }
+
+    Ok(totals)
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Standing {
+    pub entrant: String,
+    pub points: i64,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+pub fn round_robin(
+    game: &Game,
+    entrants: &[String],
+    rounds: usize,
+) -> Result<Vec<Standing>, String> {
+    for entrant in entrants {
+        parse_strategy(game, entrant)?;
+    }
+
+    let mut standings: Vec<Standing> = entrants
+        .iter()
+        .map(|entrant| Standing {
+            entrant: entrant.clone(),
+            points: 0,
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        })
+        .collect();
+
+    for i in 0..entrants.len() {
+        for j in i + 1..entrants.len() {
+            let mut a = parse_strategy(game, &entrants[i])?;
+            let mut b = parse_strategy(game, &entrants[j])?;
+            let (a_points, b_points) = play_match(game, a.as_mut(), b.as_mut(), rounds)?;
+
+            for (k, points) in [(i, a_points), (j, b_points)] {
+                standings[k].points = standings[k]
+                    .points
+                    .checked_add(points)
+                    .ok_or("tournament points overflow")?;
+            }
+
+            match a_points.cmp(&b_points) {
+                std::cmp::Ordering::Greater => {
+                    standings[i].wins += 1;
+                    standings[j].losses += 1;
+                }
+                std::cmp::Ordering::Less => {
+                    standings[i].losses += 1;
+                    standings[j].wins += 1;
+                }
+                std::cmp::Ordering::Equal => {
+                    standings[i].draws += 1;
+                    standings[j].draws += 1;
+                }
+            }
+        }
+    }
+
+    standings.sort_by(|a, b| b.points.cmp(&a.points).then(b.wins.cmp(&a.wins)));
+    Ok(standings)
+}
+
+pub fn render_leaderboard(standings: &[Standing]) -> String {
+    let mut out = format!(
+        "{:>4}  {:<20}  {:>8}  {:>4}  {:>5}  {:>6}\n",
+        "rank", "strategy", "points", "wins", "draws", "losses"
+    );
+    for (rank, standing) in standings.iter().enumerate() {
+        out += &format!(
+            "{:>4}  {:<20}  {:>8}  {:>4}  {:>5}  {:>6}\n",
+            rank + 1,
+            standing.entrant,
+            standing.points,
+            standing.wins,
+            standing.draws,
+            standing.losses
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_strategy, play_match, round_robin};
+    use crate::{parse_game, Game, Move};
+
+    #[test]
+    fn test_parse_strategy() {
+        let game = Game::standard();
+        assert!(parse_strategy(&game, "always:paper").is_ok());
+        assert!(parse_strategy(&game, "always:spock").is_err());
+        assert!(parse_strategy(&game, "random:x").is_err());
+        assert!(parse_strategy(&game, "copy:1").is_err());
+    }
+
+    #[test]
+    fn test_copy_and_frequency() {
+        let game = Game::standard();
+        let mut copy = parse_strategy(&game, "copy").unwrap();
+        let mut rock = parse_strategy(&game, "always:rock").unwrap();
+
+        // copy opens with rock and keeps copying rock: every round is a draw
+        assert_eq!(
+            play_match(&game, copy.as_mut(), rock.as_mut(), 10),
+            Ok((40, 40))
+        );
+
+        let mut frequency = parse_strategy(&game, "frequency").unwrap();
+        let mut scissors = parse_strategy(&game, "always:scissors").unwrap();
+
+        // rock beats scissors in the opening round, then frequency counters with rock
+        assert_eq!(
+            play_match(&game, frequency.as_mut(), scissors.as_mut(), 10),
+            Ok((70, 30))
+        );
+        assert_eq!(frequency.choose(&game), Move(0));
+    }
+
+    #[test]
+    fn test_random_is_seeded() {
+        let game = Game::standard();
+        let moves = |seed: &str| {
+            let mut random = parse_strategy(&game, seed).unwrap();
+            Vec::from_iter((0..20).map(|_| random.choose(&game)))
+        };
+        assert_eq!(moves("random:7"), moves("random:7"));
+        assert_ne!(moves("random:7"), moves("random:8"));
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let game = Game::standard();
+        let entrants = ["always:rock", "always:paper", "always:scissors"].map(String::from);
+        let standings = round_robin(&game, &entrants, 3).unwrap();
+
+        assert!(standings.iter().all(|s| s.wins == 1 && s.losses == 1));
+        assert_eq!(standings[0].entrant, "always:scissors");
+        assert_eq!(standings[0].points, 3 * (3 + 6) + 3 * 3);
+        assert!(round_robin(&game, &["bogus".to_string()], 3).is_err());
+    }
+
+    #[test]
+    fn test_points_beyond_i32() {
+        let game = parse_game(
+            "shape Rock A X 2000000000\nshape Paper B Y 1\nshape Scissors C Z 1\n\
+             outcomes X Y Z\npoints 0 0 0\n",
+        )
+        .unwrap();
+        let mut a = parse_strategy(&game, "always:rock").unwrap();
+        let mut b = parse_strategy(&game, "always:rock").unwrap();
+
+        assert_eq!(
+            play_match(&game, a.as_mut(), b.as_mut(), 5),
+            Ok((10_000_000_000, 10_000_000_000))
+        );
+    }
+}